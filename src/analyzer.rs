@@ -0,0 +1,139 @@
+// src/analyzer.rs
+
+use std::fmt;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Um termo extraído de um texto pelo analisador.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// Termo normalizado (o que vira nó de Termo no grafo).
+    pub term: String,
+    /// Posição da palavra no texto (0, 1, 2...), contando também as stopwords removidas.
+    pub position: usize,
+    /// Offset (em bytes) do início da palavra no texto original.
+    pub start: usize,
+    /// Offset (em bytes) do fim da palavra no texto original.
+    pub end: usize,
+}
+
+/// Analisador de texto usado tanto na indexação dos produtos quanto nas consultas.
+///
+/// Implementações devem ser determinísticas: o mesmo texto sempre gera os mesmos termos,
+/// senão a consulta deixa de encontrar o que foi indexado.
+pub trait Analyzer: fmt::Debug + Send + Sync {
+    /// Quebra o texto em tokens normalizados.
+    fn analyze(&self, text: &str) -> Vec<Token>;
+
+    /// Apenas os termos normalizados do texto, na ordem em que aparecem.
+    fn terms(&self, text: &str) -> Vec<String> {
+        self.analyze(text).into_iter().map(|token| token.term).collect()
+    }
+}
+
+/// Analisador padrão para o catálogo em português.
+///
+/// Etapas: separação de palavras (removendo pontuação, ex.: "Levi's" -> "levis", "5.1" -> "51"),
+/// minúsculas, remoção de acentos ("Verão" -> "verao"), remoção de stopwords ("de", "para"...)
+/// e um stemmer leve de plurais ("calças" -> "calca", "televisões" -> "televisao").
+#[derive(Debug, Clone)]
+pub struct PortugueseAnalyzer {
+    pub remove_stopwords: bool,
+    pub stem: bool,
+}
+
+impl Default for PortugueseAnalyzer {
+    fn default() -> Self {
+        PortugueseAnalyzer { remove_stopwords: true, stem: true }
+    }
+}
+
+impl Analyzer for PortugueseAnalyzer {
+    fn analyze(&self, text: &str) -> Vec<Token> {
+        split_words(text)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(position, (start, end))| {
+                let word: String = text[start..end].chars().filter(|c| c.is_alphanumeric()).collect();
+                let mut term = fold_diacritics(&word.to_lowercase());
+
+                if self.remove_stopwords && STOPWORDS.contains(&term.as_str()) {
+                    return None;
+                }
+                if self.stem {
+                    term = stem_plural(&term);
+                }
+                Some(Token { term, position, start, end })
+            })
+            .collect()
+    }
+}
+
+/// Remove os acentos e demais sinais diacríticos ("calça" -> "calca").
+pub fn fold_diacritics(text: &str) -> String {
+    text.nfd().filter(|c| !is_combining_mark(*c)).collect()
+}
+
+/// Stopwords do português (já sem acentos) ignoradas na indexação e nas consultas.
+const STOPWORDS: &[&str] = &[
+    "a", "ao", "aos", "as", "com", "da", "das", "de", "do", "dos", "e", "em", "na", "nas",
+    "no", "nos", "o", "os", "ou", "para", "pela", "pelas", "pelo", "pelos", "por", "sem",
+    "um", "uma", "umas", "uns",
+];
+
+// --- Funções Auxiliares (Privadas) ---
+
+/// Intervalos (em bytes) das palavras do texto. Apóstrofos e pontos entre letras/dígitos
+/// fazem parte da palavra ("levi's", "5.1"); qualquer outro caractere não alfanumérico separa.
+fn split_words(text: &str) -> Vec<(usize, usize)> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut words = Vec::new();
+    let mut start: Option<usize> = None;
+
+    for (i, &(offset, c)) in chars.iter().enumerate() {
+        let is_joiner = matches!(c, '\'' | '’' | '.')
+            && start.is_some()
+            && chars.get(i + 1).is_some_and(|(_, next)| next.is_alphanumeric());
+
+        if c.is_alphanumeric() || is_joiner {
+            start.get_or_insert(offset);
+        } else if let Some(word_start) = start.take() {
+            words.push((word_start, offset));
+        }
+    }
+    if let Some(word_start) = start {
+        words.push((word_start, text.len()));
+    }
+    words
+}
+
+/// Stemmer leve de plurais do português, aplicado a termos já sem acentos.
+fn stem_plural(term: &str) -> String {
+    // Palavras curtas ("tv", "gas") e terminações que não são plural ("ss", "us") ficam como estão.
+    if term.chars().count() <= 3 || !term.ends_with('s') || term.ends_with("ss") || term.ends_with("us") {
+        return term.to_string();
+    }
+
+    const RULES: &[(&str, &str)] = &[
+        ("oes", "ao"), // televisões -> televisão
+        ("aes", "ao"), // alemães -> alemão
+        ("ais", "al"), // animais -> animal
+        ("eis", "el"), // papéis -> papel
+        ("ois", "ol"), // lençóis -> lençol
+        ("res", "r"),  // cores -> cor
+        ("zes", "z"),  // luzes -> luz
+    ];
+    for (suffix, replacement) in RULES {
+        if let Some(stem) = term.strip_suffix(suffix) {
+            if stem.chars().count() >= 2 {
+                return format!("{}{}", stem, replacement);
+            }
+        }
+    }
+
+    if term.ends_with("is") {
+        // lápis, tênis: singular terminado em "is"
+        return term.to_string();
+    }
+    term[..term.len() - 1].to_string()
+}
@@ -0,0 +1,129 @@
+// src/autocomplete.rs
+
+use crate::analyzer::fold_diacritics;
+use crate::models::{EdgeType, GraphNode, ProductField, SearchSystem};
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use std::collections::{HashMap, HashSet};
+
+/// Termo sugerido para completar o que o usuário está digitando.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TermSuggestion {
+    /// Forma de exibição do Termo (`TermIndex::display_form`): a grafia mais frequente no
+    /// catálogo, ex.: "jeans" em vez do termo indexado "jean".
+    pub term: String,
+    /// Arestas `CONTAINS_TERM` do Termo + compras (`BOUGHT`) dos produtos que ele alcança
+    /// (cada produto contado uma vez).
+    pub popularity: usize,
+}
+
+/// Produto sugerido cujo nome completa o que o usuário está digitando.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProductSuggestion {
+    pub product_id: u32,
+    pub name: String,
+    /// Arestas `CONTAINS_TERM` vindas dos termos completados + compras (`BOUGHT`) do produto.
+    pub popularity: usize,
+}
+
+/// Sugestões de autocompletar, cada lista ordenada por popularidade.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Suggestions {
+    pub terms: Vec<TermSuggestion>,
+    pub products: Vec<ProductSuggestion>,
+}
+
+impl SearchSystem {
+    /// Sugestões de type-ahead para o texto digitado até agora.
+    ///
+    /// A última palavra é tratada como prefixo e completada pelo dicionário ordenado de
+    /// Termos (`TermIndex::with_prefix`, O(log n + k)), mantido junto com os nós de Termo.
+    /// Ela é apenas convertida para minúsculas e sem acentos: passar um prefixo pelo
+    /// analisador descartaria "no" (stopword, mas início de "notebook") e cortaria o "s"
+    /// final. A forma analisada também é consultada, para que uma palavra inteira no plural
+    /// ("jeans") encontre o termo indexado ("jean"); a sugestão volta a exibir "jeans".
+    /// As palavras anteriores, já completas, passam pelo analisador e restringem as sugestões
+    /// de produtos: o nome do produto precisa conter todas elas. Retorna até `limit` termos e
+    /// até `limit` produtos.
+    pub fn autocomplete(&self, prefix: &str, limit: usize) -> Suggestions {
+        let prefix = prefix.trim_end();
+        let (head, last_word) = prefix.rsplit_once(char::is_whitespace).unwrap_or(("", prefix));
+        let partial: String = fold_diacritics(&last_word.to_lowercase()).chars().filter(|c| c.is_alphanumeric()).collect();
+        if partial.is_empty() {
+            return Suggestions::default();
+        }
+        let complete = self.analyzer.terms(head);
+
+        let mut prefixes = vec![partial];
+        for analyzed in self.analyzer.terms(last_word) {
+            if !prefixes.contains(&analyzed) {
+                prefixes.push(analyzed);
+            }
+        }
+        let mut completions: Vec<(&str, NodeIndex)> = prefixes
+            .iter()
+            .flat_map(|partial| self.term_index.with_prefix(partial))
+            .collect();
+        completions.sort();
+        completions.dedup();
+
+        let mut purchases: HashMap<NodeIndex, usize> = HashMap::new();
+        let mut terms = Vec::new();
+        let mut product_popularity: HashMap<NodeIndex, usize> = HashMap::new();
+
+        for (term, term_node) in completions {
+            // Um produto com o termo em mais de um campo soma suas compras uma única vez.
+            let mut counted: HashSet<NodeIndex> = HashSet::new();
+            let mut popularity = 0;
+            for edge in self.graph.edges_directed(term_node, Direction::Outgoing) {
+                if edge.weight().kind != EdgeType::CONTAINS_TERM {
+                    continue;
+                }
+                let product_node = edge.target();
+                let bought = *purchases
+                    .entry(product_node)
+                    .or_insert_with(|| self.purchase_count(product_node));
+                popularity += 1;
+                if counted.insert(product_node) {
+                    popularity += bought;
+                }
+
+                if edge.weight().field == Some(ProductField::Name) {
+                    *product_popularity.entry(product_node).or_insert(bought) += 1;
+                }
+            }
+            terms.push(TermSuggestion { term: self.term_index.display_form(term).to_string(), popularity });
+        }
+
+        let mut products: Vec<ProductSuggestion> = product_popularity
+            .into_iter()
+            .filter_map(|(product_node, popularity)| match self.graph.node_weight(product_node) {
+                Some(GraphNode::Product(product)) => {
+                    let name_terms: HashSet<String> = self.analyzer.terms(&product.name).into_iter().collect();
+                    complete.iter().all(|term| name_terms.contains(term)).then(|| ProductSuggestion {
+                        product_id: product.id,
+                        name: product.name.clone(),
+                        popularity,
+                    })
+                }
+                _ => None,
+            })
+            .collect();
+
+        terms.sort_by(|a, b| b.popularity.cmp(&a.popularity).then_with(|| a.term.cmp(&b.term)));
+        terms.truncate(limit);
+        products.sort_by(|a, b| b.popularity.cmp(&a.popularity).then(a.product_id.cmp(&b.product_id)));
+        products.truncate(limit);
+
+        Suggestions { terms, products }
+    }
+
+    /// Quantidade de arestas `BOUGHT` que chegam ao produto.
+    pub(crate) fn purchase_count(&self, product_node: NodeIndex) -> usize {
+        self.graph
+            .edges_directed(product_node, Direction::Incoming)
+            .filter(|edge| edge.weight().kind == EdgeType::BOUGHT)
+            .count()
+    }
+}
@@ -0,0 +1,315 @@
+// src/csv_import.rs
+
+use crate::error::MegaStoreError;
+use crate::graph_builder::build_system;
+use crate::models::{Catalog, Interaction, InteractionType, Product, SearchSystem, User};
+use csv::StringRecord;
+use std::collections::HashSet;
+use std::io::Read;
+
+/// Mapeamento das colunas do CSV de produtos para os campos de `Product`.
+#[derive(Debug, Clone)]
+pub struct ProductColumns {
+    pub id: String,
+    pub name: String,
+    pub brand: String,
+    pub category: String,
+    pub tags: String,
+    /// Coluna opcional com a data de cadastro (timestamp Unix). Se não existir no cabeçalho, fica `None`.
+    pub created_at: String,
+    /// Separador usado dentro da coluna de tags (ex.: "tv;smart").
+    pub tag_separator: char,
+}
+
+impl Default for ProductColumns {
+    fn default() -> Self {
+        ProductColumns {
+            id: "id".to_string(),
+            name: "name".to_string(),
+            brand: "brand".to_string(),
+            category: "category".to_string(),
+            tags: "tags".to_string(),
+            created_at: "created_at".to_string(),
+            tag_separator: ';',
+        }
+    }
+}
+
+/// Mapeamento das colunas do CSV de clientes para os campos de `User`.
+#[derive(Debug, Clone)]
+pub struct UserColumns {
+    pub id: String,
+    pub name: String,
+}
+
+impl Default for UserColumns {
+    fn default() -> Self {
+        UserColumns { id: "id".to_string(), name: "name".to_string() }
+    }
+}
+
+/// Mapeamento das colunas do CSV de interações (user_id, product_id, evento).
+#[derive(Debug, Clone)]
+pub struct InteractionColumns {
+    pub user_id: String,
+    pub product_id: String,
+    pub event: String,
+    /// Coluna opcional com a nota (1 a 5) das interações `RATED`. Se não existir no cabeçalho, fica `None`.
+    pub rating: String,
+    /// Coluna opcional com o momento da interação (timestamp Unix).
+    pub timestamp: String,
+    /// Coluna opcional com a quantidade comprada.
+    pub quantity: String,
+}
+
+impl Default for InteractionColumns {
+    fn default() -> Self {
+        InteractionColumns {
+            user_id: "user_id".to_string(),
+            product_id: "product_id".to_string(),
+            event: "event".to_string(),
+            rating: "rating".to_string(),
+            timestamp: "timestamp".to_string(),
+            quantity: "quantity".to_string(),
+        }
+    }
+}
+
+/// Configuração completa da importação CSV.
+#[derive(Debug, Clone)]
+pub struct CsvMapping {
+    pub products: ProductColumns,
+    pub users: UserColumns,
+    pub interactions: InteractionColumns,
+    /// Delimitador de campos (padrão: `,`).
+    pub delimiter: u8,
+}
+
+impl Default for CsvMapping {
+    fn default() -> Self {
+        CsvMapping {
+            products: ProductColumns::default(),
+            users: UserColumns::default(),
+            interactions: InteractionColumns::default(),
+            delimiter: b',',
+        }
+    }
+}
+
+/// Erro associado a uma linha específica de um arquivo CSV.
+#[derive(Debug)]
+pub struct RowError {
+    /// Nome lógico do arquivo ("products", "users" ou "interactions").
+    pub source: &'static str,
+    /// Número da linha no arquivo (o cabeçalho é a linha 1).
+    pub line: u64,
+    pub error: MegaStoreError,
+}
+
+/// Registros lidos com sucesso e erros por linha.
+#[derive(Debug)]
+pub struct CsvRecords<T> {
+    pub records: Vec<T>,
+    pub errors: Vec<RowError>,
+    /// Linha de origem de cada registro em `records` (mesma ordem).
+    lines: Vec<u64>,
+}
+
+/// Resultado da importação completa: o sistema construído e os erros por linha.
+#[derive(Debug)]
+pub struct CsvImport {
+    pub system: SearchSystem,
+    pub errors: Vec<RowError>,
+}
+
+/// Lê produtos de um CSV usando o mapeamento de colunas informado.
+pub fn read_products_csv<R: Read>(reader: R, columns: &ProductColumns, delimiter: u8) -> Result<CsvRecords<Product>, MegaStoreError> {
+    let mut csv_reader = csv::ReaderBuilder::new().delimiter(delimiter).trim(csv::Trim::All).from_reader(reader);
+    let headers = csv_reader.headers()?.clone();
+    let id = column_position(&headers, &columns.id)?;
+    let name = column_position(&headers, &columns.name)?;
+    let brand = column_position(&headers, &columns.brand)?;
+    let category = column_position(&headers, &columns.category)?;
+    let tags = column_position(&headers, &columns.tags)?;
+    let created_at = column_position(&headers, &columns.created_at).ok();
+
+    let mut seen = HashSet::new();
+    read_rows(&mut csv_reader, "products", |record| {
+        let product_id = parse_id(record, id, &columns.id)?;
+        if seen.contains(&product_id) {
+            return Err(MegaStoreError::InvalidData(format!("produto ID {} duplicado no catálogo", product_id)));
+        }
+        let product = Product {
+            id: product_id,
+            name: required_field(record, name, &columns.name)?.to_string(),
+            brand: field(record, brand).to_string(),
+            category: required_field(record, category, &columns.category)?.to_string(),
+            tags: field(record, tags)
+                .split(columns.tag_separator)
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| !tag.is_empty())
+                .collect(),
+            created_at: optional_number(record, created_at, &columns.created_at)?,
+        };
+        seen.insert(product_id);
+        Ok(product)
+    })
+}
+
+/// Lê clientes de um CSV usando o mapeamento de colunas informado.
+pub fn read_users_csv<R: Read>(reader: R, columns: &UserColumns, delimiter: u8) -> Result<CsvRecords<User>, MegaStoreError> {
+    let mut csv_reader = csv::ReaderBuilder::new().delimiter(delimiter).trim(csv::Trim::All).from_reader(reader);
+    let headers = csv_reader.headers()?.clone();
+    let id = column_position(&headers, &columns.id)?;
+    let name = column_position(&headers, &columns.name)?;
+
+    let mut seen = HashSet::new();
+    read_rows(&mut csv_reader, "users", |record| {
+        let user_id = parse_id(record, id, &columns.id)?;
+        if seen.contains(&user_id) {
+            return Err(MegaStoreError::InvalidData(format!("cliente ID {} duplicado no catálogo", user_id)));
+        }
+        let user = User { id: user_id, name: required_field(record, name, &columns.name)?.to_string() };
+        seen.insert(user_id);
+        Ok(user)
+    })
+}
+
+/// Lê interações de um CSV usando o mapeamento de colunas informado.
+pub fn read_interactions_csv<R: Read>(reader: R, columns: &InteractionColumns, delimiter: u8) -> Result<CsvRecords<Interaction>, MegaStoreError> {
+    let mut csv_reader = csv::ReaderBuilder::new().delimiter(delimiter).trim(csv::Trim::All).from_reader(reader);
+    let headers = csv_reader.headers()?.clone();
+    let user_id = column_position(&headers, &columns.user_id)?;
+    let product_id = column_position(&headers, &columns.product_id)?;
+    let event = column_position(&headers, &columns.event)?;
+    let rating = column_position(&headers, &columns.rating).ok();
+    let timestamp = column_position(&headers, &columns.timestamp).ok();
+    let quantity = column_position(&headers, &columns.quantity).ok();
+
+    read_rows(&mut csv_reader, "interactions", |record| {
+        Ok(Interaction {
+            user_id: parse_id(record, user_id, &columns.user_id)?,
+            product_id: parse_id(record, product_id, &columns.product_id)?,
+            interaction_type: field(record, event)
+                .parse::<InteractionType>()
+                .map_err(MegaStoreError::InvalidData)?,
+            rating: optional_number(record, rating, &columns.rating)?,
+            timestamp: optional_number(record, timestamp, &columns.timestamp)?,
+            quantity: optional_number(record, quantity, &columns.quantity)?,
+        })
+    })
+}
+
+/// Importa produtos, clientes e (opcionalmente) interações em CSV e constrói o sistema.
+///
+/// Linhas inválidas (IDs não numéricos ou repetidos, campos obrigatórios vazios, interações
+/// com IDs desconhecidos) são ignoradas e relatadas em `CsvImport::errors`. Cabeçalhos sem as
+/// colunas mapeadas geram erro imediato.
+pub fn import_catalog_csv<P: Read, U: Read, I: Read>(
+    products: P,
+    users: U,
+    interactions: Option<I>,
+    mapping: &CsvMapping,
+) -> Result<CsvImport, MegaStoreError> {
+    let products = read_products_csv(products, &mapping.products, mapping.delimiter)?;
+    let users = read_users_csv(users, &mapping.users, mapping.delimiter)?;
+
+    let mut errors = products.errors;
+    errors.extend(users.errors);
+
+    let mut system = build_system(Catalog {
+        products: products.records,
+        users: users.records,
+        interactions: Vec::new(),
+    })?;
+
+    if let Some(reader) = interactions {
+        let interactions = read_interactions_csv(reader, &mapping.interactions, mapping.delimiter)?;
+        errors.extend(interactions.errors);
+
+        for (interaction, line) in interactions.records.iter().zip(interactions.lines) {
+            if let Err(error) = system.record_interaction(interaction) {
+                errors.push(RowError { source: "interactions", line, error });
+            }
+        }
+    }
+
+    Ok(CsvImport { system, errors })
+}
+
+// --- Funções Auxiliares (Privadas) ---
+
+/// Percorre as linhas do CSV convertendo cada uma com `parse_row` e separando os erros.
+fn read_rows<R: Read, T>(
+    csv_reader: &mut csv::Reader<R>,
+    source: &'static str,
+    mut parse_row: impl FnMut(&StringRecord) -> Result<T, MegaStoreError>,
+) -> Result<CsvRecords<T>, MegaStoreError> {
+    let mut result = CsvRecords { records: Vec::new(), errors: Vec::new(), lines: Vec::new() };
+
+    for record in csv_reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(err) => {
+                // Erros de E/S abortam; linhas malformadas são apenas relatadas.
+                if err.is_io_error() {
+                    return Err(err.into());
+                }
+                let line = err.position().map(|p| p.line()).unwrap_or(0);
+                result.errors.push(RowError { source, line, error: err.into() });
+                continue;
+            }
+        };
+        let line = record.position().map(|p| p.line()).unwrap_or(0);
+
+        match parse_row(&record) {
+            Ok(value) => {
+                result.records.push(value);
+                result.lines.push(line);
+            }
+            Err(error) => result.errors.push(RowError { source, line, error }),
+        }
+    }
+
+    Ok(result)
+}
+
+fn column_position(headers: &StringRecord, column: &str) -> Result<usize, MegaStoreError> {
+    headers
+        .iter()
+        .position(|header| header.eq_ignore_ascii_case(column))
+        .ok_or_else(|| MegaStoreError::InvalidData(format!("coluna '{}' ausente no cabeçalho do CSV", column)))
+}
+
+fn field(record: &StringRecord, position: usize) -> &str {
+    record.get(position).unwrap_or("")
+}
+
+fn required_field<'a>(record: &'a StringRecord, position: usize, column: &str) -> Result<&'a str, MegaStoreError> {
+    match field(record, position) {
+        "" => Err(MegaStoreError::InvalidData(format!("campo '{}' vazio", column))),
+        value => Ok(value),
+    }
+}
+
+/// Valor numérico de uma coluna opcional: `None` se a coluna não existe ou a célula está vazia.
+fn optional_number<T: std::str::FromStr>(
+    record: &StringRecord,
+    position: Option<usize>,
+    column: &str,
+) -> Result<Option<T>, MegaStoreError> {
+    match position.map(|position| field(record, position)) {
+        None | Some("") => Ok(None),
+        Some(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| MegaStoreError::InvalidData(format!("valor '{}' inválido para '{}'", value, column))),
+    }
+}
+
+fn parse_id(record: &StringRecord, position: usize, column: &str) -> Result<u32, MegaStoreError> {
+    let value = field(record, position);
+    value
+        .parse()
+        .map_err(|_| MegaStoreError::InvalidData(format!("valor '{}' inválido para o ID '{}'", value, column)))
+}
@@ -0,0 +1,74 @@
+// src/error.rs
+
+use std::fmt;
+
+/// Erros da crate: carga de dados, construção do grafo, busca e recomendação.
+#[derive(Debug)]
+pub enum MegaStoreError {
+    /// Falha de leitura do arquivo ou fluxo de dados.
+    Io(std::io::Error),
+    /// O documento JSON não pôde ser interpretado.
+    Parse(serde_json::Error),
+    /// Interação que referencia um Cliente ou Produto inexistente no catálogo.
+    DanglingInteraction { user_id: u32, product_id: u32 },
+    /// ID de Cliente que não está indexado no sistema.
+    UnknownUser(u32),
+    /// ID de Produto que não está indexado no sistema.
+    UnknownProduct(u32),
+    /// Registro com valores inválidos ou incompletos.
+    InvalidData(String),
+    /// O arquivo CSV não pôde ser lido ou interpretado.
+    Csv(csv::Error),
+    /// Consulta com sintaxe inválida; `position` é o offset (em bytes) do problema na consulta.
+    QueryParse { position: usize, message: String },
+}
+
+impl fmt::Display for MegaStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MegaStoreError::Io(err) => write!(f, "erro de E/S: {}", err),
+            MegaStoreError::Parse(err) => write!(f, "erro ao interpretar os dados: {}", err),
+            MegaStoreError::DanglingInteraction { user_id, product_id } => write!(
+                f,
+                "interação (cliente {}, produto {}) referencia um registro inexistente",
+                user_id, product_id
+            ),
+            MegaStoreError::UnknownUser(id) => write!(f, "cliente ID {} não encontrado", id),
+            MegaStoreError::UnknownProduct(id) => write!(f, "produto ID {} não encontrado", id),
+            MegaStoreError::InvalidData(msg) => write!(f, "dados inválidos: {}", msg),
+            MegaStoreError::Csv(err) => write!(f, "erro ao ler CSV: {}", err),
+            MegaStoreError::QueryParse { position, message } => {
+                write!(f, "consulta inválida (posição {}): {}", position, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MegaStoreError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MegaStoreError::Io(err) => Some(err),
+            MegaStoreError::Parse(err) => Some(err),
+            MegaStoreError::Csv(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for MegaStoreError {
+    fn from(err: std::io::Error) -> Self {
+        MegaStoreError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for MegaStoreError {
+    fn from(err: serde_json::Error) -> Self {
+        MegaStoreError::Parse(err)
+    }
+}
+
+impl From<csv::Error> for MegaStoreError {
+    fn from(err: csv::Error) -> Self {
+        MegaStoreError::Csv(err)
+    }
+}
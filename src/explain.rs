@@ -0,0 +1,73 @@
+// src/explain.rs
+
+use crate::models::GraphEdge;
+use crate::search::SearchFilters;
+use serde::Serialize;
+
+/// Como um Termo do grafo foi alcançado a partir da consulta.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchKind {
+    /// O Termo é exatamente o termo da consulta.
+    Exact,
+    /// O Termo contém o termo da consulta, ou está contido nele.
+    Partial,
+    /// Expansão pelo dicionário de sinônimos.
+    Synonym,
+    /// Correspondência aproximada (erro de digitação).
+    Fuzzy,
+    /// Termo de uma frase entre aspas.
+    Phrase,
+    /// Termo de um campo qualificado (`brand:samsung`).
+    Field,
+}
+
+/// Contribuição de um nó de Termo para a pontuação do produto.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TermMatch {
+    /// Termo (ou termos, em frases e campos) da consulta que levou a este Termo.
+    pub query_term: String,
+    /// Termo do grafo que casou.
+    pub term: String,
+    /// Índice do nó de Termo no grafo.
+    pub term_node: usize,
+    pub kind: MatchKind,
+    /// Arestas `CONTAINS_TERM` percorridas do Termo até o produto (campo e posição de cada ocorrência).
+    pub edges: Vec<GraphEdge>,
+    /// Pontuação BM25 do Termo para o produto.
+    pub bm25: f64,
+    /// Fator aplicado ao BM25 (sinônimo ou aproximação; 1.0 nos demais casos).
+    pub penalty: f64,
+    /// Valor somado à pontuação do produto (`bm25 * penalty`).
+    pub contribution: f64,
+}
+
+/// Tipo de bônus multiplicado na pontuação.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BoostKind {
+    /// Termos vizinhos da consulta próximos no produto.
+    Proximity,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Boost {
+    pub kind: BoostKind,
+    pub factor: f64,
+}
+
+/// Por que um produto apareceu com esta pontuação. Gerada com `SearchRequest::with_explain`
+/// e serializável em JSON (`serde_json::to_string(&explanation)`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Explanation {
+    pub product_id: u32,
+    pub matched_terms: usize,
+    /// Soma das contribuições dos termos, antes dos bônus.
+    pub base_score: f64,
+    pub boosts: Vec<Boost>,
+    /// Pontuação final: `base_score` multiplicada pelos bônus.
+    pub score: f64,
+    pub terms: Vec<TermMatch>,
+    /// Filtros de marca, categoria e tag que o produto atendeu.
+    pub filters: SearchFilters,
+}
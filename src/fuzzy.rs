@@ -0,0 +1,159 @@
+// src/fuzzy.rs
+
+use crate::models::{EdgeType, GraphNode, SearchSystem};
+use crate::query::{positive_words, QueryWord};
+use crate::term_index::TermIndex;
+use petgraph::graph::NodeIndex;
+use petgraph::Direction;
+
+/// Distância de Damerau-Levenshtein (variante "optimal string alignment") entre dois termos:
+/// inserções, remoções, substituições e transposições de caracteres vizinhos ("samsumg" -> "samsung" = 1).
+pub fn damerau_levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // Três linhas da matriz de programação dinâmica: i-2, i-1 e i.
+    let mut before_previous: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current: Vec<usize> = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            current[j] = (previous[j] + 1) // remoção
+                .min(current[j - 1] + 1) // inserção
+                .min(previous[j - 1] + cost); // substituição
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before_previous[j - 2] + 1); // transposição
+            }
+        }
+        std::mem::swap(&mut before_previous, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// Quantidade máxima de edições tolerada para um termo da consulta, proporcional ao seu tamanho.
+/// Termos muito curtos ("tv", "lg") não são expandidos, para não casar com qualquer coisa.
+pub fn max_edits(term: &str) -> usize {
+    match term.chars().count() {
+        0..=2 => 0,
+        3..=5 => 1,
+        _ => 2,
+    }
+}
+
+/// Termos do dicionário a até `max_edits(term)` edições de `term`, com a distância de cada um.
+/// Retorna os mais próximos primeiro (e em ordem alfabética em caso de empate).
+pub fn fuzzy_matches(index: &TermIndex, term: &str) -> Vec<(NodeIndex, usize)> {
+    let limit = max_edits(term);
+    if limit == 0 {
+        return Vec::new();
+    }
+
+    let length = term.chars().count();
+    // Só os termos cujo tamanho difere em até `limit` caracteres podem estar a `limit` edições.
+    let mut matches: Vec<(&str, NodeIndex, usize)> = index
+        .with_length_between(length.saturating_sub(limit), length + limit)
+        .filter_map(|(candidate, node)| {
+            let distance = damerau_levenshtein(term, candidate);
+            (distance <= limit).then_some((candidate, node, distance))
+        })
+        .collect();
+
+    matches.sort_by(|a, b| a.2.cmp(&b.2).then(a.0.cmp(b.0)));
+    matches.into_iter().map(|(_, node, distance)| (node, distance)).collect()
+}
+
+/// Sugestões de correção ("Você quis dizer...?") para uma consulta.
+///
+/// Cada palavra fora de negações que não existe no dicionário é trocada pelos Termos de
+/// `fuzzy_matches`, dos mais próximos (menor distância de edição e, no empate, maior frequência,
+/// isto é, mais arestas `CONTAINS_TERM` saindo do Termo), exibidos pela grafia do catálogo
+/// (`TermIndex::display_form`). Só o trecho da palavra é substituído no texto original: campos,
+/// frases, negações e palavras sem correção ficam como foram digitados ("brand:samsumg -tv" ->
+/// "brand:samsung -tv"). A primeira sugestão usa a melhor correção de todas as palavras; as
+/// seguintes variam uma palavra por vez. Retorna até `limit` consultas distintas.
+pub fn spelling_suggestions(system: &SearchSystem, query: &str, limit: usize) -> Vec<String> {
+    let Ok(words) = positive_words(query, system.analyzer.as_ref()) else {
+        return Vec::new();
+    };
+
+    // Para cada palavra desconhecida: as correções candidatas, da melhor para a pior.
+    let mut corrections: Vec<(&QueryWord, Vec<&str>)> = Vec::new();
+    for word in &words {
+        let known = if word.exact {
+            system.term_index.contains(&word.term)
+        } else {
+            !system.term_index.partial_matches(&word.term).is_empty()
+        };
+        if known {
+            continue;
+        }
+
+        let mut candidates: Vec<(&str, usize, usize)> = fuzzy_matches(&system.term_index, &word.term)
+            .into_iter()
+            .filter_map(|(node, distance)| match system.graph.node_weight(node) {
+                Some(GraphNode::Term(candidate)) => Some((candidate.term.as_str(), distance, term_frequency(system, node))),
+                _ => None,
+            })
+            .collect();
+        candidates.sort_by(|a, b| a.1.cmp(&b.1).then(b.2.cmp(&a.2)).then(a.0.cmp(b.0)));
+
+        let mut options: Vec<&str> = Vec::new();
+        for (candidate, _, _) in candidates {
+            let display = system.term_index.display_form(candidate);
+            if !options.contains(&display) {
+                options.push(display);
+            }
+        }
+        // Palavra sem correção: fica como está e as demais ainda são corrigidas.
+        if !options.is_empty() {
+            corrections.push((word, options));
+        }
+    }
+
+    if corrections.is_empty() {
+        return Vec::new();
+    }
+
+    let best: Vec<&str> = corrections.iter().map(|(_, options)| options[0]).collect();
+    let mut suggestions = vec![rewrite_query(query, &corrections, &best)];
+    for (position, (_, options)) in corrections.iter().enumerate() {
+        for option in options.iter().skip(1) {
+            let mut variant = best.clone();
+            variant[position] = option;
+            let text = rewrite_query(query, &corrections, &variant);
+            if !suggestions.contains(&text) {
+                suggestions.push(text);
+            }
+        }
+    }
+
+    suggestions.truncate(limit);
+    suggestions
+}
+
+/// A consulta original com o trecho de cada palavra corrigida trocado pela substituta
+/// correspondente em `replacements`.
+fn rewrite_query(query: &str, corrections: &[(&QueryWord, Vec<&str>)], replacements: &[&str]) -> String {
+    let mut text = String::with_capacity(query.len());
+    let mut copied = 0;
+    for ((word, _), replacement) in corrections.iter().zip(replacements) {
+        text.push_str(&query[copied..word.start]);
+        text.push_str(replacement);
+        copied = word.end;
+    }
+    text.push_str(&query[copied..]);
+    text
+}
+
+/// Quantidade de arestas `CONTAINS_TERM` que saem do nó de Termo.
+fn term_frequency(system: &SearchSystem, term_node: NodeIndex) -> usize {
+    system.graph
+        .edges_directed(term_node, Direction::Outgoing)
+        .filter(|edge| edge.weight().kind == EdgeType::CONTAINS_TERM)
+        .count()
+}
@@ -1,29 +1,87 @@
 // src/graph_builder.rs
 
+use crate::analyzer::{Analyzer, PortugueseAnalyzer};
+use crate::error::MegaStoreError;
+use crate::term_index::TermIndex;
+use crate::synonyms::SynonymMap;
 use crate::models::{
     MegaStoreGraph, SearchSystem, Product, User, 
-    GraphNode, EdgeType, Term, 
+    GraphNode, GraphEdge, EdgeType, Term, Catalog, Interaction, InteractionType,
+    IndexStats, ProductField,
 };
-use petgraph::graph::NodeIndex;
-use std::collections::HashMap;
+use petgraph::graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Fração máxima do catálogo que uma tag pode cobrir para gerar arestas `RELATED_BY_TAG`.
+/// Tags mais comuns ("promo", "novo") ligariam quase todos os produtos entre si (cliques densos).
+pub const RELATED_BY_TAG_MAX_DF_RATIO: f64 = 0.4;
+
+/// Tags presentes em até esta quantidade de produtos sempre geram arestas `RELATED_BY_TAG`,
+/// para que `RELATED_BY_TAG_MAX_DF_RATIO` não descarte as tags de catálogos pequenos.
+pub const RELATED_BY_TAG_MIN_DF_CAP: usize = 10;
+
+/// Quantidade máxima de arestas `RELATED_BY_TAG` saindo de cada produto (os mais relacionados).
+pub const RELATED_BY_TAG_MAX_NEIGHBORS: usize = 20;
 
 /// Função que carrega dados simulados e constrói o sistema de busca.
 // PRECISA DE 'pub'
 pub fn build_system_from_data() -> SearchSystem { 
-    // ... (restante da lógica do grafo)
+    // --- DADOS SIMULADOS ---
+    let catalog = Catalog {
+        products: create_mock_products(),
+        users: create_mock_users(),
+        interactions: create_mock_interactions(),
+    };
+    // ---------------------------------------------------
+
+    build_system(catalog).expect("Os dados simulados devem ser consistentes")
+}
+
+/// Carrega um catálogo JSON (produtos, clientes e interações) do arquivo em `path`
+/// e constrói o sistema de busca.
+pub fn build_system_from_path<P: AsRef<Path>>(path: P) -> Result<SearchSystem, MegaStoreError> {
+    let file = File::open(path)?;
+    build_system_from_reader(BufReader::new(file))
+}
+
+/// Lê um catálogo JSON de qualquer fonte (`Read`) e constrói o sistema de busca.
+///
+/// Formato esperado:
+/// `{"products": [...], "users": [...], "interactions": [{"user_id": 1, "product_id": 101, "type": "BOUGHT"}]}`
+pub fn build_system_from_reader<R: Read>(reader: R) -> Result<SearchSystem, MegaStoreError> {
+    let catalog: Catalog = serde_json::from_reader(reader)?;
+    build_system(catalog)
+}
+
+/// Constrói o Grafo e as Tabelas Hash a partir de um catálogo já carregado em memória,
+/// usando o analisador padrão (`PortugueseAnalyzer`).
+pub fn build_system(catalog: Catalog) -> Result<SearchSystem, MegaStoreError> {
+    build_system_with_analyzer(catalog, Arc::new(PortugueseAnalyzer::default()))
+}
+
+/// Constrói o sistema com um analisador de texto próprio. O mesmo analisador é usado
+/// depois nas consultas e nas atualizações incrementais.
+pub fn build_system_with_analyzer(catalog: Catalog, analyzer: Arc<dyn Analyzer>) -> Result<SearchSystem, MegaStoreError> {
+    let started = Instant::now();
     let mut graph = MegaStoreGraph::new();
     let mut product_id_to_index = HashMap::new();
     let mut user_id_to_index = HashMap::new();
 
-    // --- DADOS SIMULADOS ---
-    let products = create_mock_products();
-    let users = create_mock_users();
-    let interactions = create_mock_interactions();
-    // ---------------------------------------------------
+    let Catalog { products, users, interactions } = catalog;
 
     // 2. ADICIONA NÓS DE PRODUTO e preenche o HashMap de produtos
     for p in products {
         let id = p.id;
+        if product_id_to_index.contains_key(&id) {
+            return Err(MegaStoreError::InvalidData(format!("produto ID {} duplicado no catálogo", id)));
+        }
         let index = graph.add_node(GraphNode::Product(p));
         product_id_to_index.insert(id, index);
     }
@@ -31,23 +89,19 @@ pub fn build_system_from_data() -> SearchSystem {
     // 3. ADICIONA NÓS DE CLIENTE e preenche o HashMap de usuários
     for u in users {
         let id = u.id;
+        if user_id_to_index.contains_key(&id) {
+            return Err(MegaStoreError::InvalidData(format!("cliente ID {} duplicado no catálogo", id)));
+        }
         let index = graph.add_node(GraphNode::User(u));
         user_id_to_index.insert(id, index);
     }
 
-    // 4. ADICIONA INTERAÇÕES (ARESTAS User -> Product)
-    for (user_id, product_id, edge_type) in interactions {
-        let user_index = *user_id_to_index.get(&user_id).expect("User not found");
-        let product_index = *product_id_to_index.get(&product_id).expect("Product not found");
-
-        graph.add_edge(user_index, product_index, edge_type);
-    }
-
-    // 5. CRIA NÓS DE TERMOS DE BUSCA (Indexação) e arestas de relacionamentos Item-Item
-    let mut term_to_index: HashMap<String, NodeIndex> = HashMap::new();
+    // 4. CRIA NÓS DE TERMOS DE BUSCA (Indexação) e arestas de relacionamentos Item-Item
+    let mut term_index = TermIndex::new();
+    let mut index_stats = IndexStats::default();
 
     // Iteramos sobre todos os produtos já criados
-    for (product_id, product_node_index) in product_id_to_index.clone() { // <-- CLONA O MAPA DE ÍNDICES!
+    for (_, product_node_index) in product_id_to_index.clone() { // <-- CLONA O MAPA DE ÍNDICES!
         
         // 1. Extrai o dado do Produto (fazendo uma cópia ou clone do dado dentro do nó)
         let product = if let Some(GraphNode::Product(p)) = graph.node_weight(product_node_index) {
@@ -57,51 +111,369 @@ pub fn build_system_from_data() -> SearchSystem {
         };
         
         // Indexação por Termo (agora sem borrow imutável do grafo)
-        let terms: Vec<String> = product.name.to_lowercase().split_whitespace().map(|s| s.to_string()).collect();
-        let all_terms = [terms, product.tags.clone()].concat();
-
-        for term_str in all_terms {
-            let term_str = term_str.to_lowercase();
-            
-            // Pega ou cria o Nó de Termo de Busca
-            let term_index = *term_to_index.entry(term_str.clone())
-                .or_insert_with(|| graph.add_node(GraphNode::Term(Term { term: term_str }))); // Mutável OK
-
-            // Cria a aresta de indexação (Term -> Produto)
-            graph.add_edge(term_index, product_node_index, EdgeType::CONTAINS_TERM); // Mutável OK
-        }
+        index_product_terms(&mut graph, &mut term_index, &mut index_stats, analyzer.as_ref(), product_node_index, &product);
 
         // Relacionamentos Item-Item (Produto <-> Produto) - AGORA COM O CLONE DO DADO 'product'
         for (other_id, other_index) in &product_id_to_index {
             if product.id != *other_id {
                  if let Some(GraphNode::Product(other_product)) = graph.node_weight(*other_index) {
                     if product.category == other_product.category {
-                        graph.add_edge(product_node_index, *other_index, EdgeType::RELATED_BY_CATEGORY);
+                        graph.add_edge(product_node_index, *other_index, EdgeType::RELATED_BY_CATEGORY.into());
                     }
                 }
             }
         }
     }
 
-    println!("Grafo construído com sucesso! Nós: {}, Arestas: {}", graph.node_count(), graph.edge_count());
+    // Relacionamentos por Tags, depois de indexar todos os produtos (o IDF usa o catálogo inteiro)
+    let product_count = product_id_to_index.len();
+    let tag_relations: Vec<(NodeIndex, Vec<(NodeIndex, f64)>)> = product_id_to_index
+        .values()
+        .map(|&product_node_index| (product_node_index, strongest_tag_neighbors(tag_neighbors(&graph, product_node_index, product_count))))
+        .collect();
+    for (product_node_index, neighbors) in tag_relations {
+        for (other_index, weight) in neighbors {
+            graph.add_edge(product_node_index, other_index, GraphEdge::related_by_tag(weight));
+        }
+    }
 
-    // 6. Retorna a estrutura completa
-    SearchSystem {
+    let mut system = SearchSystem {
         graph,
         product_id_to_index,
         user_id_to_index,
+        term_index,
+        index_stats,
+        analyzer,
+        synonyms: SynonymMap::new(),
+    };
+
+    // 5. ADICIONA INTERAÇÕES (ARESTAS User -> Product)
+    for interaction in &interactions {
+        // IDs desconhecidos viram `DanglingInteraction`; dados inválidos passam como estão.
+        system.record_interaction(interaction).map_err(|err| match err {
+            MegaStoreError::UnknownUser(_) | MegaStoreError::UnknownProduct(_) => MegaStoreError::DanglingInteraction {
+                user_id: interaction.user_id,
+                product_id: interaction.product_id,
+            },
+            other => other,
+        })?;
+    }
+
+    log::info!(
+        nodes = system.graph.node_count(),
+        edges = system.graph.edge_count(),
+        elapsed_us = started.elapsed().as_micros() as u64;
+        "Grafo construído com sucesso"
+    );
+
+    // 6. Retorna a estrutura completa
+    Ok(system)
+}
+
+// --- Atualizações Incrementais ---
+
+impl SearchSystem {
+    /// Insere um produto novo ou substitui os dados de um produto existente.
+    ///
+    /// Os nós de Termo e as arestas `CONTAINS_TERM`/`RELATED_BY_CATEGORY`/`RELATED_BY_TAG` do
+    /// produto são recriados; as interações (`BOUGHT`, `VIEWED`...) já registradas são preservadas.
+    /// O peso das arestas por tag dos demais produtos entre si não é recalculado, e o produto
+    /// só entra entre os relacionados de outro se superar a relação mais fraca dele.
+    pub fn upsert_product(&mut self, product: Product) -> NodeIndex {
+        let product_node_index = match self.product_id_to_index.get(&product.id) {
+            Some(&index) => {
+                self.unindex_product(index);
+                if let Some(node) = self.graph.node_weight_mut(index) {
+                    *node = GraphNode::Product(product.clone());
+                }
+                index
+            }
+            None => {
+                let index = self.graph.add_node(GraphNode::Product(product.clone()));
+                self.product_id_to_index.insert(product.id, index);
+                index
+            }
+        };
+
+        index_product_terms(
+            &mut self.graph,
+            &mut self.term_index,
+            &mut self.index_stats,
+            self.analyzer.as_ref(),
+            product_node_index,
+            &product,
+        );
+
+        // Relacionamentos Item-Item nos dois sentidos
+        for (other_id, other_index) in &self.product_id_to_index {
+            if product.id == *other_id {
+                continue;
+            }
+            if let Some(GraphNode::Product(other_product)) = self.graph.node_weight(*other_index) {
+                if product.category == other_product.category {
+                    self.graph.add_edge(product_node_index, *other_index, EdgeType::RELATED_BY_CATEGORY.into());
+                    self.graph.add_edge(*other_index, product_node_index, EdgeType::RELATED_BY_CATEGORY.into());
+                }
+            }
+        }
+
+        // Relacionamentos por Tags: apenas os produtos das listas dos Termos de tag do produto
+        let neighbors = tag_neighbors(&self.graph, product_node_index, self.product_id_to_index.len());
+        for &(other_index, weight) in &strongest_tag_neighbors(neighbors.clone()) {
+            self.graph.add_edge(product_node_index, other_index, GraphEdge::related_by_tag(weight));
+        }
+        for (other_index, weight) in neighbors {
+            offer_tag_neighbor(&mut self.graph, other_index, product_node_index, weight);
+        }
+
+        product_node_index
+    }
+
+    /// Insere um cliente novo ou atualiza os dados de um cliente existente (mantendo o histórico).
+    pub fn upsert_user(&mut self, user: User) -> NodeIndex {
+        match self.user_id_to_index.get(&user.id) {
+            Some(&index) => {
+                if let Some(node) = self.graph.node_weight_mut(index) {
+                    *node = GraphNode::User(user);
+                }
+                index
+            }
+            None => {
+                let id = user.id;
+                let index = self.graph.add_node(GraphNode::User(user));
+                self.user_id_to_index.insert(id, index);
+                index
+            }
+        }
+    }
+
+    /// Cria a aresta User -> Product correspondente a uma interação.
+    /// Retorna `UnknownUser`/`UnknownProduct` se algum dos IDs não estiver indexado.
+    pub fn record_interaction(&mut self, interaction: &Interaction) -> Result<(), MegaStoreError> {
+        let user_index = *self.user_id_to_index.get(&interaction.user_id)
+            .ok_or(MegaStoreError::UnknownUser(interaction.user_id))?;
+        let product_index = *self.product_id_to_index.get(&interaction.product_id)
+            .ok_or(MegaStoreError::UnknownProduct(interaction.product_id))?;
+
+        if let Some(rating) = interaction.rating {
+            if !(1..=5).contains(&rating) {
+                return Err(MegaStoreError::InvalidData(format!("nota {} fora do intervalo de 1 a 5", rating)));
+            }
+        }
+        if interaction.quantity == Some(0) {
+            return Err(MegaStoreError::InvalidData("quantidade da interação deve ser maior que zero".to_string()));
+        }
+
+        self.graph.add_edge(user_index, product_index, GraphEdge::interaction(interaction));
+        Ok(())
+    }
+
+    /// Remove um produto, todas as suas arestas e os nós de Termo que ficarem sem produtos.
+    pub fn remove_product(&mut self, product_id: u32) -> Result<Product, MegaStoreError> {
+        let index = self.product_id_to_index.remove(&product_id)
+            .ok_or(MegaStoreError::UnknownProduct(product_id))?;
+
+        self.unindex_product(index);
+        match self.graph.remove_node(index) {
+            Some(GraphNode::Product(product)) => Ok(product),
+            _ => Err(MegaStoreError::UnknownProduct(product_id)),
+        }
+    }
+
+    /// Remove um cliente e todas as suas interações.
+    pub fn remove_user(&mut self, user_id: u32) -> Result<User, MegaStoreError> {
+        let index = self.user_id_to_index.remove(&user_id)
+            .ok_or(MegaStoreError::UnknownUser(user_id))?;
+
+        match self.graph.remove_node(index) {
+            Some(GraphNode::User(user)) => Ok(user),
+            _ => Err(MegaStoreError::UnknownUser(user_id)),
+        }
+    }
+
+    /// Remove as arestas de indexação, de categoria e de tags de um produto (e as grafias que ele
+    /// registrou), descartando os nós de Termo que deixarem de apontar para algum produto.
+    fn unindex_product(&mut self, product_node_index: NodeIndex) {
+        if let Some(GraphNode::Product(product)) = self.graph.node_weight(product_node_index) {
+            for &field in ProductField::ALL.iter() {
+                for (term, _, spelling) in product_field_tokens(self.analyzer.as_ref(), product, field) {
+                    self.term_index.remove_spelling(&term, &spelling);
+                }
+            }
+        }
+
+        let mut stale_edges = Vec::new();
+        let mut touched_terms = Vec::new();
+
+        for edge in self.graph.edges_directed(product_node_index, Direction::Incoming) {
+            match edge.weight().kind {
+                EdgeType::CONTAINS_TERM => {
+                    stale_edges.push(edge.id());
+                    touched_terms.push(edge.source());
+                    if let Some(field) = edge.weight().field {
+                        if let Some(length) = self.index_stats.field_lengths.get_mut(&field) {
+                            *length = length.saturating_sub(1);
+                        }
+                    }
+                }
+                EdgeType::RELATED_BY_CATEGORY | EdgeType::RELATED_BY_TAG => stale_edges.push(edge.id()),
+                _ => {}
+            }
+        }
+        for edge in self.graph.edges_directed(product_node_index, Direction::Outgoing) {
+            if matches!(edge.weight().kind, EdgeType::RELATED_BY_CATEGORY | EdgeType::RELATED_BY_TAG) {
+                stale_edges.push(edge.id());
+            }
+        }
+
+        for edge in stale_edges {
+            self.graph.remove_edge(edge);
+        }
+
+        for term_index in touched_terms {
+            let is_orphan = self.graph.edges_directed(term_index, Direction::Outgoing).next().is_none();
+            if is_orphan {
+                if let Some(GraphNode::Term(term)) = self.graph.remove_node(term_index) {
+                    self.term_index.remove(&term.term);
+                }
+            }
+        }
+    }
+}
+
+/// Termos de cada campo indexado de um produto, segundo o analisador, com a posição de cada um no
+/// campo e a grafia original da palavra (em minúsculas, sem pontuação).
+pub(crate) fn product_field_tokens(analyzer: &dyn Analyzer, product: &Product, field: ProductField) -> Vec<(String, usize, String)> {
+    let texts: Vec<&str> = match field {
+        ProductField::Name => vec![&product.name],
+        ProductField::Tags => product.tags.iter().map(String::as_str).collect(),
+        ProductField::Brand => vec![&product.brand],
+        ProductField::Category => vec![&product.category],
+    };
+
+    // As tags são numeradas em sequência, deixando uma posição vazia entre elas para que
+    // uma frase não atravesse duas tags.
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+    for text in texts {
+        let analyzed = analyzer.analyze(text);
+        let next_offset = analyzed.last().map_or(offset, |token| offset + token.position + 2);
+        tokens.extend(analyzed.into_iter().map(|token| {
+            let spelling: String = text[token.start..token.end].chars().filter(|c| c.is_alphanumeric()).collect();
+            (token.term, offset + token.position, spelling.to_lowercase())
+        }));
+        offset = next_offset;
+    }
+    tokens
+}
+
+/// Cria (ou reaproveita) os nós de Termo do nome, das tags, da marca e da categoria de um
+/// produto e as arestas de indexação Term -> Produto (uma por ocorrência, marcada com o campo e a posição).
+fn index_product_terms(
+    graph: &mut MegaStoreGraph,
+    term_index: &mut TermIndex,
+    index_stats: &mut IndexStats,
+    analyzer: &dyn Analyzer,
+    product_node_index: NodeIndex,
+    product: &Product,
+) {
+    let all_terms = ProductField::ALL
+        .iter()
+        .flat_map(|&field| {
+            product_field_tokens(analyzer, product, field)
+                .into_iter()
+                .map(move |(term, position, spelling)| (field, term, position, spelling))
+        });
+
+    for (field, term_str, position, spelling) in all_terms {
+        term_index.add_spelling(&term_str, &spelling);
+
+        // Pega ou cria o Nó de Termo de Busca
+        let term_node_index = match term_index.get(&term_str) {
+            Some(index) => index,
+            None => {
+                let index = graph.add_node(GraphNode::Term(Term { term: term_str.clone() }));
+                term_index.insert(term_str, index);
+                index
+            }
+        };
+
+        // Cria a aresta de indexação (Term -> Produto)
+        graph.add_edge(term_node_index, product_node_index, GraphEdge::term(field, position)); // Mutável OK
+        *index_stats.field_lengths.entry(field).or_insert(0) += 1;
     }
 }
 
 // --- Funções Auxiliares (Privadas) ---
 
+/// Produtos que compartilham tags com o produto e o peso de cada relação: a soma do IDF
+/// (`ln(1 + N / df)`) das tags em comum. Os candidatos vêm das listas de produtos de cada Termo
+/// de tag, ignorando as tags presentes em mais de `RELATED_BY_TAG_MAX_DF_RATIO` do catálogo
+/// (e em mais de `RELATED_BY_TAG_MIN_DF_CAP` produtos).
+fn tag_neighbors(graph: &MegaStoreGraph, product_node_index: NodeIndex, product_count: usize) -> HashMap<NodeIndex, f64> {
+    let tag_terms: HashSet<NodeIndex> = graph
+        .edges_directed(product_node_index, Direction::Incoming)
+        .filter(|edge| edge.weight().kind == EdgeType::CONTAINS_TERM && edge.weight().field == Some(ProductField::Tags))
+        .map(|edge| edge.source())
+        .collect();
+
+    let max_df = (RELATED_BY_TAG_MAX_DF_RATIO * product_count as f64).max(RELATED_BY_TAG_MIN_DF_CAP as f64);
+    let mut neighbors: HashMap<NodeIndex, f64> = HashMap::new();
+    for term_node_index in tag_terms {
+        let postings: HashSet<NodeIndex> = graph
+            .edges_directed(term_node_index, Direction::Outgoing)
+            .filter(|edge| edge.weight().field == Some(ProductField::Tags))
+            .map(|edge| edge.target())
+            .collect();
+        if postings.len() as f64 > max_df {
+            continue;
+        }
+        let idf = (1.0 + product_count as f64 / postings.len() as f64).ln();
+        for other_index in postings {
+            if other_index != product_node_index {
+                *neighbors.entry(other_index).or_insert(0.0) += idf;
+            }
+        }
+    }
+    neighbors
+}
+
+/// Os `RELATED_BY_TAG_MAX_NEIGHBORS` vizinhos de maior peso (índice do nó como desempate estável).
+fn strongest_tag_neighbors(neighbors: HashMap<NodeIndex, f64>) -> Vec<(NodeIndex, f64)> {
+    let mut neighbors: Vec<(NodeIndex, f64)> = neighbors.into_iter().collect();
+    neighbors.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    neighbors.truncate(RELATED_BY_TAG_MAX_NEIGHBORS);
+    neighbors
+}
+
+/// Cria a aresta `RELATED_BY_TAG` de `product_node_index` para `other_index` se houver vaga entre
+/// os relacionados do produto ou se ela superar a relação mais fraca, que é então removida.
+fn offer_tag_neighbor(graph: &mut MegaStoreGraph, product_node_index: NodeIndex, other_index: NodeIndex, weight: f64) {
+    let existing: Vec<(EdgeIndex, NodeIndex, f64)> = graph
+        .edges_directed(product_node_index, Direction::Outgoing)
+        .filter(|edge| edge.weight().kind == EdgeType::RELATED_BY_TAG)
+        .map(|edge| (edge.id(), edge.target(), edge.weight().weight.unwrap_or(0.0)))
+        .collect();
+
+    if existing.len() >= RELATED_BY_TAG_MAX_NEIGHBORS {
+        let weakest = existing.iter().min_by(|a, b| a.2.total_cmp(&b.2).then(b.1.cmp(&a.1)));
+        match weakest {
+            Some(&(edge, _, weakest_weight)) if weight > weakest_weight => {
+                graph.remove_edge(edge);
+            }
+            _ => return,
+        }
+    }
+    graph.add_edge(product_node_index, other_index, GraphEdge::related_by_tag(weight));
+}
+
 fn create_mock_products() -> Vec<Product> {
     vec![
-        Product { id: 101, name: "Smart TV 4K LG 55".to_string(), brand: "LG".to_string(), category: "Eletrônicos".to_string(), tags: vec!["tv".to_string(), "smart".to_string()] },
-        Product { id: 102, name: "Soundbar JBL 5.1".to_string(), brand: "JBL".to_string(), category: "Eletrônicos".to_string(), tags: vec!["audio".to_string(), "speaker".to_string()] },
-        Product { id: 103, name: "Vestido Floral Verão".to_string(), brand: "Zara".to_string(), category: "Vestuário".to_string(), tags: vec!["roupa".to_string(), "verao".to_string()] },
-        Product { id: 104, name: "Smart TV QLED Samsung 65".to_string(), brand: "Samsung".to_string(), category: "Eletrônicos".to_string(), tags: vec!["tv".to_string(), "qled".to_string()] },
-        Product { id: 105, name: "Calça Jeans Slim Fit".to_string(), brand: "Levi's".to_string(), category: "Vestuário".to_string(), tags: vec!["jeans".to_string(), "calca".to_string()] },
+        Product { id: 101, name: "Smart TV 4K LG 55".to_string(), brand: "LG".to_string(), category: "Eletrônicos".to_string(), tags: vec!["tv".to_string(), "smart".to_string()], created_at: Some(1704067200) },
+        Product { id: 102, name: "Soundbar JBL 5.1".to_string(), brand: "JBL".to_string(), category: "Eletrônicos".to_string(), tags: vec!["audio".to_string(), "speaker".to_string()], created_at: Some(1706745600) },
+        Product { id: 103, name: "Vestido Floral Verão".to_string(), brand: "Zara".to_string(), category: "Vestuário".to_string(), tags: vec!["roupa".to_string(), "verao".to_string()], created_at: Some(1717200000) },
+        Product { id: 104, name: "Smart TV QLED Samsung 65".to_string(), brand: "Samsung".to_string(), category: "Eletrônicos".to_string(), tags: vec!["tv".to_string(), "qled".to_string()], created_at: Some(1714521600) },
+        Product { id: 105, name: "Calça Jeans Slim Fit".to_string(), brand: "Levi's".to_string(), category: "Vestuário".to_string(), tags: vec!["jeans".to_string(), "calca".to_string()], created_at: Some(1709251200) },
     ]
 }
 
@@ -113,14 +485,17 @@ fn create_mock_users() -> Vec<User> {
     ]
 }
 
-fn create_mock_interactions() -> Vec<(u32, u32, EdgeType)> {
-    // (user_id, product_id, edge_type)
+fn create_mock_interactions() -> Vec<Interaction> {
+    // (user_id, product_id, interaction_type)
     vec![
-        (1, 101, EdgeType::BOUGHT),  // Alice comprou TV LG
-        (1, 102, EdgeType::VIEWED),  // Alice viu Soundbar
-        (2, 104, EdgeType::BOUGHT),  // Bob comprou TV Samsung
-        (2, 102, EdgeType::BOUGHT),  // Bob comprou Soundbar
-        (3, 103, EdgeType::BOUGHT),  // Charlie comprou Vestido
-        (3, 105, EdgeType::VIEWED),  // Charlie viu Calça Jeans
+        (1, 101, InteractionType::Bought),  // Alice comprou TV LG
+        (1, 102, InteractionType::Viewed),  // Alice viu Soundbar
+        (2, 104, InteractionType::Bought),  // Bob comprou TV Samsung
+        (2, 102, InteractionType::Bought),  // Bob comprou Soundbar
+        (3, 103, InteractionType::Bought),  // Charlie comprou Vestido
+        (3, 105, InteractionType::Viewed),  // Charlie viu Calça Jeans
     ]
+    .into_iter()
+    .map(|(user_id, product_id, interaction_type)| Interaction { user_id, product_id, interaction_type, rating: None, timestamp: None, quantity: None })
+    .collect()
 }
\ No newline at end of file
@@ -0,0 +1,116 @@
+// src/highlight.rs
+
+use crate::analyzer::Analyzer;
+use crate::models::{Product, ProductField};
+use crate::search::SearchHit;
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// Trechos de um produto que casaram com a consulta, como offsets em bytes do texto original.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Highlights {
+    /// Trechos de `Product.name`.
+    pub name: Vec<Range<usize>>,
+    /// Tags que casaram: índice em `Product.tags` e os trechos dentro da tag.
+    pub tags: Vec<(usize, Vec<Range<usize>>)>,
+}
+
+impl Highlights {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_empty() && self.tags.is_empty()
+    }
+}
+
+/// Marcadores inseridos antes e depois de cada trecho destacado.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HighlightMarkers {
+    pub pre: String,
+    pub post: String,
+}
+
+impl HighlightMarkers {
+    pub fn new(pre: impl Into<String>, post: impl Into<String>) -> Self {
+        HighlightMarkers { pre: pre.into(), post: post.into() }
+    }
+}
+
+impl Default for HighlightMarkers {
+    fn default() -> Self {
+        HighlightMarkers::new("<mark>", "</mark>")
+    }
+}
+
+/// Insere os marcadores em volta dos trechos de `text`. Trechos sobrepostos são unidos e
+/// trechos fora do texto ou fora dos limites de caracteres são ignorados.
+pub fn highlight(text: &str, spans: &[Range<usize>], markers: &HighlightMarkers) -> String {
+    let mut spans: Vec<Range<usize>> = spans
+        .iter()
+        .filter(|span| span.start < span.end && text.get(span.start..span.end).is_some())
+        .cloned()
+        .collect();
+    spans.sort_by_key(|span| (span.start, span.end));
+
+    let mut rendered = String::with_capacity(text.len());
+    let mut cursor = 0;
+    let mut spans = spans.into_iter().peekable();
+    while let Some(mut span) = spans.next() {
+        while let Some(next) = spans.next_if(|next| next.start <= span.end) {
+            span.end = span.end.max(next.end);
+        }
+        let start = span.start.max(cursor);
+        rendered.push_str(&text[cursor..start]);
+        rendered.push_str(&markers.pre);
+        rendered.push_str(&text[start..span.end]);
+        rendered.push_str(&markers.post);
+        cursor = span.end;
+    }
+    rendered.push_str(&text[cursor..]);
+    rendered
+}
+
+impl SearchHit {
+    /// Nome do produto com os trechos que casaram com a consulta destacados.
+    pub fn highlighted_name(&self, markers: &HighlightMarkers) -> String {
+        highlight(&self.product.name, &self.highlights.name, markers)
+    }
+
+    /// Apenas as tags que casaram com a consulta, com os trechos destacados.
+    pub fn highlighted_tags(&self, markers: &HighlightMarkers) -> Vec<String> {
+        self.highlights
+            .tags
+            .iter()
+            .filter_map(|(index, spans)| self.product.tags.get(*index).map(|tag| highlight(tag, spans, markers)))
+            .collect()
+    }
+}
+
+/// Localiza no nome e nas tags do produto as palavras cujo termo está em `terms`.
+///
+/// Cada termo vem com o campo a que a consulta o restringiu (`None` vale para qualquer campo).
+/// O texto é reanalisado com o mesmo analisador da indexação, então os offsets apontam para
+/// a palavra original ("Calça" para o termo "calca").
+pub(crate) fn find_highlights(
+    analyzer: &dyn Analyzer,
+    product: &Product,
+    terms: &HashSet<(Option<ProductField>, String)>,
+) -> Highlights {
+    let spans = |text: &str, field: ProductField| -> Vec<Range<usize>> {
+        analyzer
+            .analyze(text)
+            .into_iter()
+            .filter(|token| terms.contains(&(None, token.term.clone())) || terms.contains(&(Some(field), token.term.clone())))
+            .map(|token| token.start..token.end)
+            .collect()
+    };
+
+    Highlights {
+        name: spans(&product.name, ProductField::Name),
+        tags: product
+            .tags
+            .iter()
+            .enumerate()
+            .map(|(index, tag)| (index, spans(tag, ProductField::Tags)))
+            .filter(|(_, spans)| !spans.is_empty())
+            .collect(),
+    }
+}
@@ -0,0 +1,98 @@
+// src/ingest.rs
+
+use crate::error::MegaStoreError;
+use crate::models::{Interaction, SearchSystem};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Quantidade máxima de erros detalhados guardados no relatório.
+/// Os demais são apenas contabilizados em `IngestReport::lines_failed`.
+pub const MAX_REPORTED_ERRORS: usize = 1000;
+
+/// Erro associado a uma linha específica do arquivo JSONL.
+#[derive(Debug)]
+pub struct LineError {
+    /// Número da linha (começando em 1).
+    pub line: usize,
+    pub error: MegaStoreError,
+}
+
+/// Resumo de uma ingestão de interações.
+#[derive(Debug, Default)]
+pub struct IngestReport {
+    /// Linhas não vazias processadas.
+    pub lines_read: usize,
+    /// Interações que viraram arestas no grafo.
+    pub interactions_added: usize,
+    /// Linhas descartadas (UTF-8 inválido, JSON malformado ou IDs desconhecidos).
+    pub lines_failed: usize,
+    /// Detalhes dos primeiros `MAX_REPORTED_ERRORS` erros.
+    pub errors: Vec<LineError>,
+}
+
+impl IngestReport {
+    fn record_error(&mut self, line: usize, error: MegaStoreError) {
+        self.lines_failed += 1;
+        if self.errors.len() < MAX_REPORTED_ERRORS {
+            self.errors.push(LineError { line, error });
+        }
+    }
+}
+
+/// Lê interações no formato JSON Lines (uma por linha) e adiciona as arestas
+/// de interação (`BOUGHT`, `VIEWED`, `ADDED_TO_CART`, `WISHLISTED`, `RATED`) correspondentes a um sistema já construído.
+///
+/// Cada linha tem o formato `{"user_id": 1, "product_id": 101, "event": "BOUGHT"}`.
+/// O arquivo é lido linha a linha, sem carregar tudo em memória. Linhas inválidas
+/// não interrompem a ingestão: são registradas no relatório. Apenas falhas de E/S
+/// abortam o processo.
+pub fn ingest_interactions_jsonl<R: BufRead>(system: &mut SearchSystem, mut reader: R) -> Result<IngestReport, MegaStoreError> {
+    let mut report = IngestReport::default();
+    let mut buffer = Vec::new();
+    let mut line_number = 0;
+
+    loop {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            break;
+        }
+        line_number += 1;
+
+        // Bytes fora do UTF-8 invalidam apenas a linha, não o restante do arquivo
+        let line = match std::str::from_utf8(&buffer) {
+            Ok(line) => line,
+            Err(err) => {
+                report.lines_read += 1;
+                report.record_error(line_number, MegaStoreError::InvalidData(format!("linha não é UTF-8 válido: {}", err)));
+                continue;
+            }
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+        report.lines_read += 1;
+
+        let interaction: Interaction = match serde_json::from_str(line) {
+            Ok(interaction) => interaction,
+            Err(err) => {
+                report.record_error(line_number, MegaStoreError::Parse(err));
+                continue;
+            }
+        };
+
+        match system.record_interaction(&interaction) {
+            Ok(()) => report.interactions_added += 1,
+            Err(err) => report.record_error(line_number, err),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Abre o arquivo JSONL em `path` e executa `ingest_interactions_jsonl`.
+pub fn ingest_interactions_jsonl_path<P: AsRef<Path>>(system: &mut SearchSystem, path: P) -> Result<IngestReport, MegaStoreError> {
+    let file = File::open(path)?;
+    ingest_interactions_jsonl(system, BufReader::new(file))
+}
@@ -0,0 +1,137 @@
+// src/item_similarity.rs
+
+use crate::error::MegaStoreError;
+use crate::models::{EdgeType, GraphNode, Product, ProductField, SearchSystem};
+use crate::recommender::{cosine, jaccard};
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+/// Peso de cada sinal na semelhança entre dois produtos.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ItemSimilarityWeights {
+    /// Mesma categoria (aresta `RELATED_BY_CATEGORY`).
+    pub category: f64,
+    /// Peso da aresta `RELATED_BY_TAG` (soma do IDF das tags em comum).
+    pub tags: f64,
+    /// Jaccard entre os termos do nome e da marca.
+    pub terms: f64,
+    /// Clientes que compraram os dois produtos, normalizado pelo cosseno
+    /// (`em comum / sqrt(compradores de A * compradores de B)`).
+    pub co_purchase: f64,
+}
+
+impl Default for ItemSimilarityWeights {
+    fn default() -> Self {
+        ItemSimilarityWeights { category: 1.0, tags: 1.0, terms: 1.0, co_purchase: 3.0 }
+    }
+}
+
+/// Produtos semelhantes a `product_id` (carrossel "produtos semelhantes"), cada um com sua
+/// pontuação, usando os pesos padrão. Veja `similar_products_with_weights`.
+pub fn similar_products(system: &SearchSystem, product_id: u32, k: usize) -> Result<Vec<(Product, f64)>, MegaStoreError> {
+    similar_products_with_weights(system, product_id, k, &ItemSimilarityWeights::default())
+}
+
+/// Produtos semelhantes a `product_id`: soma ponderada da mesma categoria, das tags e termos em
+/// comum e das compras em comum. Os candidatos são os produtos alcançáveis pelo grafo a partir
+/// do produto (arestas de categoria e de tags, nós de Termo compartilhados e clientes que
+/// o compraram).
+///
+/// Os resultados são ordenados pela pontuação (e pelo ID em caso de empate) e limitados a `k`.
+/// Retorna `MegaStoreError::UnknownProduct` se o produto não estiver indexado.
+pub fn similar_products_with_weights(
+    system: &SearchSystem,
+    product_id: u32,
+    k: usize,
+    weights: &ItemSimilarityWeights,
+) -> Result<Vec<(Product, f64)>, MegaStoreError> {
+    let graph = &system.graph;
+    let started = Instant::now();
+
+    let product_index = *system.product_id_to_index.get(&product_id)
+        .ok_or(MegaStoreError::UnknownProduct(product_id))?;
+
+    let mut related: HashSet<NodeIndex> = HashSet::new();
+    let mut tag_relations: HashMap<NodeIndex, f64> = HashMap::new();
+    for edge in graph.edges_directed(product_index, Direction::Outgoing) {
+        match edge.weight().kind {
+            EdgeType::RELATED_BY_CATEGORY => {
+                related.insert(edge.target());
+            }
+            EdgeType::RELATED_BY_TAG => {
+                tag_relations.insert(edge.target(), edge.weight().weight.unwrap_or(0.0));
+            }
+            _ => {}
+        }
+    }
+    let terms = term_vector(system, product_index, &[ProductField::Name, ProductField::Brand]);
+    let buyers = buyer_vector(system, product_index);
+
+    // Candidatos: vizinhos por categoria e por tags, por Termo compartilhado e por compra em comum
+    let mut candidates: HashSet<NodeIndex> = related.iter().chain(tag_relations.keys()).copied().collect();
+    for &term_index in terms.keys() {
+        candidates.extend(graph.edges_directed(term_index, Direction::Outgoing).map(|edge| edge.target()));
+    }
+    for &user_index in buyers.keys() {
+        candidates.extend(
+            graph
+                .edges_directed(user_index, Direction::Outgoing)
+                .filter(|edge| edge.weight().kind == EdgeType::BOUGHT)
+                .map(|edge| edge.target()),
+        );
+    }
+    candidates.remove(&product_index);
+
+    let mut similar: Vec<(Product, f64)> = candidates
+        .into_iter()
+        .filter_map(|candidate_index| {
+            let GraphNode::Product(candidate) = graph.node_weight(candidate_index)? else {
+                return None;
+            };
+            let category = if related.contains(&candidate_index) { 1.0 } else { 0.0 };
+            let score = weights.category * category
+                + weights.tags * tag_relations.get(&candidate_index).copied().unwrap_or(0.0)
+                + weights.terms * jaccard(&terms, &term_vector(system, candidate_index, &[ProductField::Name, ProductField::Brand]))
+                + weights.co_purchase * cosine(&buyers, &buyer_vector(system, candidate_index));
+            (score > 0.0).then(|| (candidate.clone(), score))
+        })
+        .collect();
+    similar.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.id.cmp(&b.0.id)));
+    similar.truncate(k);
+
+    log::info!(
+        product_id = product_id,
+        result_count = similar.len(),
+        elapsed_us = started.elapsed().as_micros() as u64;
+        "Produtos semelhantes gerados"
+    );
+
+    Ok(similar)
+}
+
+// --- Funções Auxiliares (Privadas) ---
+
+/// Nós de Termo que apontam para o produto em algum dos campos (o valor é sempre 1.0).
+fn term_vector(system: &SearchSystem, product_index: NodeIndex, fields: &[ProductField]) -> HashMap<NodeIndex, f64> {
+    system
+        .graph
+        .edges_directed(product_index, Direction::Incoming)
+        .filter(|edge| edge.weight().kind == EdgeType::CONTAINS_TERM)
+        .filter(|edge| edge.weight().field.is_some_and(|field| fields.contains(&field)))
+        .map(|edge| (edge.source(), 1.0))
+        .collect()
+}
+
+/// Clientes que compraram o produto (arestas `BOUGHT`), cada um valendo 1.0 independentemente
+/// da quantidade de compras. Visualizações e demais interações não contam.
+fn buyer_vector(system: &SearchSystem, product_index: NodeIndex) -> HashMap<NodeIndex, f64> {
+    system
+        .graph
+        .edges_directed(product_index, Direction::Incoming)
+        .filter(|edge| edge.weight().kind == EdgeType::BOUGHT)
+        .map(|edge| (edge.source(), 1.0))
+        .collect()
+}
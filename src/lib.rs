@@ -2,11 +2,25 @@
 
 // As funções e structs públicas que queremos expor ao binário (main.rs) e aos testes
 pub mod models; 
+pub mod error;
+pub mod analyzer;
+pub mod term_index;
+pub mod fuzzy;
+pub mod synonyms;
 pub mod graph_builder; 
+pub mod query;
 pub mod search; 
 pub mod recommender;
+pub mod item_similarity;
+pub mod autocomplete;
+pub mod highlight;
+pub mod explain;
+pub mod ingest;
+pub mod csv_import;
 
 // Re-exporta as funções principais no nível da crate para fácil acesso nos testes
-pub use graph_builder::build_system_from_data;
-pub use search::search_by_term;
-pub use recommender::recommend_for_user;
\ No newline at end of file
+pub use error::MegaStoreError;
+pub use graph_builder::{build_system_from_data, build_system_from_path, build_system_from_reader};
+pub use search::{search, search_by_term, search_scored, try_search_by_term, MatchMode, SearchFilters, SearchRequest, SortOrder};
+pub use recommender::{recommend_for_user, try_recommend_for_user};
+pub use item_similarity::similar_products;
\ No newline at end of file
@@ -7,7 +7,50 @@ use megastore_busca_otimizada::{
     recommend_for_user
 };
 
+/// Logger mínimo da demonstração: escreve em stderr os diagnósticos da biblioteca.
+/// O nível é definido pela variável de ambiente `MEGASTORE_LOG` (ex.: "info", "debug").
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let mut fields = FieldWriter(String::new());
+        let _ = record.key_values().visit(&mut fields);
+        eprintln!("[{}] {}{}", record.level(), record.args(), fields.0);
+    }
+
+    fn flush(&self) {}
+}
+
+/// Acumula os campos estruturados (chave=valor) de um registro de log.
+struct FieldWriter(String);
+
+impl<'kvs> log::kv::VisitSource<'kvs> for FieldWriter {
+    fn visit_pair(&mut self, key: log::kv::Key<'kvs>, value: log::kv::Value<'kvs>) -> Result<(), log::kv::Error> {
+        self.0.push_str(&format!(" {}={}", key, value));
+        Ok(())
+    }
+}
+
+fn init_logging() {
+    let level = std::env::var("MEGASTORE_LOG")
+        .ok()
+        .and_then(|value| value.parse::<log::LevelFilter>().ok())
+        .unwrap_or(log::LevelFilter::Off);
+
+    if log::set_logger(&StderrLogger).is_ok() {
+        log::set_max_level(level);
+    }
+}
+
 fn main() {
+    init_logging();
     println!("Inicializando o Sistema de Busca da MegaStore...");
     
     // 1. Constrói o sistema de busca completo (Grafo + HashMaps)
@@ -1,5 +1,8 @@
 // src/models.rs
 
+use crate::analyzer::Analyzer;
+use crate::synonyms::SynonymMap;
+use crate::term_index::TermIndex;
 use petgraph::graph::NodeIndex;
 use serde::{Serialize, Deserialize};
 
@@ -12,15 +15,29 @@ pub enum NodeType {
 }
 
 /// Define os tipos de ARESTAS (Relacionamentos) entre os nós.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 pub enum EdgeType { 
     BOUGHT,              // User -> Product (Cliente comprou)
     VIEWED,              // User -> Product (Cliente visualizou)
+    ADDED_TO_CART,       // User -> Product (Cliente adicionou ao carrinho)
+    WISHLISTED,          // User -> Product (Cliente adicionou à lista de desejos)
+    RATED,               // User -> Product (Cliente avaliou)
     RELATED_BY_CATEGORY, // Product <-> Product (Similaridade para recomendação Item-Item)
     RELATED_BY_TAG,      // Product <-> Product (Similaridade por Tags)
     CONTAINS_TERM,       // Term -> Product (Link de indexação da busca)
 }
 
+impl EdgeType {
+    /// Arestas de interação Cliente -> Produto (usadas pelo recomendador).
+    pub fn is_interaction(self) -> bool {
+        matches!(
+            self,
+            EdgeType::BOUGHT | EdgeType::VIEWED | EdgeType::ADDED_TO_CART | EdgeType::WISHLISTED | EdgeType::RATED
+        )
+    }
+}
+
 /// STRUCT: Produto. Representa um item no catálogo.
 /// Os derives Hash/Eq são necessários para usar Product em HashSets (para busca).
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -30,6 +47,9 @@ pub struct Product {
     pub brand: String,
     pub category: String,
     pub tags: Vec<String>,
+    /// Data de cadastro no catálogo (timestamp Unix, em segundos), usada na ordenação "mais novos".
+    #[serde(default)]
+    pub created_at: Option<u64>,
 }
 
 /// STRUCT: Cliente (User). Representa um usuário ou cliente da MegaStore.
@@ -39,6 +59,134 @@ pub struct User {
     pub name: String,
 }
 
+/// Campos do Produto que são indexados como Termos de busca.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ProductField {
+    Name,
+    Tags,
+    Brand,
+    Category,
+}
+
+impl ProductField {
+    pub const ALL: [ProductField; 4] = [ProductField::Name, ProductField::Tags, ProductField::Brand, ProductField::Category];
+}
+
+/// Conteúdo de cada ARESTA do grafo: o tipo do relacionamento e seus metadados.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct GraphEdge {
+    pub kind: EdgeType,
+    /// Campo do produto de onde o termo foi extraído (apenas em arestas `CONTAINS_TERM`).
+    pub field: Option<ProductField>,
+    /// Posição da palavra dentro do campo (apenas em arestas `CONTAINS_TERM`), usada nas
+    /// buscas por frase e no bônus de proximidade.
+    pub position: Option<usize>,
+    /// Nota de 1 a 5 (apenas em arestas `RATED`).
+    pub rating: Option<u8>,
+    /// Momento da interação (timestamp Unix, em segundos), usado no decaimento temporal das recomendações.
+    pub timestamp: Option<u64>,
+    /// Quantidade de unidades (apenas em arestas `BOUGHT`).
+    pub quantity: Option<u32>,
+    /// Força da relação (apenas em arestas `RELATED_BY_TAG`: soma do IDF das tags em comum).
+    pub weight: Option<f64>,
+}
+
+impl GraphEdge {
+    /// Aresta de indexação Term -> Product para um termo extraído de `field` na posição `position`.
+    pub fn term(field: ProductField, position: usize) -> Self {
+        GraphEdge { kind: EdgeType::CONTAINS_TERM, field: Some(field), position: Some(position), ..EdgeType::CONTAINS_TERM.into() }
+    }
+
+    /// Aresta Product -> Product entre produtos com tags em comum, com o peso da relação.
+    pub fn related_by_tag(weight: f64) -> Self {
+        GraphEdge { weight: Some(weight), ..EdgeType::RELATED_BY_TAG.into() }
+    }
+
+    /// Aresta de interação User -> Product.
+    pub fn interaction(interaction: &Interaction) -> Self {
+        GraphEdge {
+            rating: interaction.rating,
+            timestamp: interaction.timestamp,
+            quantity: interaction.quantity,
+            ..EdgeType::from(interaction.interaction_type).into()
+        }
+    }
+}
+
+impl From<EdgeType> for GraphEdge {
+    fn from(kind: EdgeType) -> Self {
+        GraphEdge { kind, field: None, position: None, rating: None, timestamp: None, quantity: None, weight: None }
+    }
+}
+
+/// Tipo de interação explícita entre um Cliente e um Produto (usado na carga de dados).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InteractionType {
+    Bought,      // Cliente comprou
+    Viewed,      // Cliente visualizou
+    AddedToCart, // Cliente adicionou ao carrinho
+    Wishlisted,  // Cliente adicionou à lista de desejos
+    Rated,       // Cliente avaliou (nota em `Interaction::rating`)
+}
+
+impl From<InteractionType> for EdgeType {
+    fn from(kind: InteractionType) -> Self {
+        match kind {
+            InteractionType::Bought => EdgeType::BOUGHT,
+            InteractionType::Viewed => EdgeType::VIEWED,
+            InteractionType::AddedToCart => EdgeType::ADDED_TO_CART,
+            InteractionType::Wishlisted => EdgeType::WISHLISTED,
+            InteractionType::Rated => EdgeType::RATED,
+        }
+    }
+}
+
+impl std::str::FromStr for InteractionType {
+    type Err = String;
+
+    /// Aceita o nome do evento sem diferenciar maiúsculas ("BOUGHT", "viewed"...).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "BOUGHT" => Ok(InteractionType::Bought),
+            "VIEWED" => Ok(InteractionType::Viewed),
+            "ADDED_TO_CART" => Ok(InteractionType::AddedToCart),
+            "WISHLISTED" => Ok(InteractionType::Wishlisted),
+            "RATED" => Ok(InteractionType::Rated),
+            other => Err(format!("tipo de interação desconhecido: '{}'", other)),
+        }
+    }
+}
+
+/// STRUCT: Interação. Registro (user_id, product_id, tipo) que vira uma aresta User -> Product.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub user_id: u32,
+    pub product_id: u32,
+    #[serde(rename = "type", alias = "event")]
+    pub interaction_type: InteractionType,
+    /// Nota de 1 a 5, para interações `RATED`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+    /// Momento da interação (timestamp Unix, em segundos).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+    /// Quantidade de unidades compradas, para interações `BOUGHT` (ausente = 1).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<u32>,
+}
+
+/// STRUCT: Catálogo. Documento completo (produtos, clientes e interações) usado para construir o sistema.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Catalog {
+    #[serde(default)]
+    pub products: Vec<Product>,
+    #[serde(default)]
+    pub users: Vec<User>,
+    #[serde(default)]
+    pub interactions: Vec<Interaction>,
+}
+
 /// STRUCT: Termo. Representa uma palavra-chave usada para indexar produtos.
 #[derive(Debug, Clone)]
 pub struct Term { 
@@ -66,8 +214,27 @@ impl GraphNode {
 }
 
 /// Alias de tipo para o Grafo Direcionado Principal.
-/// Define o Grafo como: DiGraph<Conteúdo do Nó, Peso da Aresta>
-pub type MegaStoreGraph = petgraph::graph::DiGraph<GraphNode, EdgeType>;
+/// Define o Grafo como: StableDiGraph<Conteúdo do Nó, Conteúdo da Aresta>
+/// A versão "estável" mantém os `NodeIndex` válidos após remoções, o que permite
+/// atualizar o grafo sem invalidar as Tabelas Hash de acesso rápido.
+pub type MegaStoreGraph = petgraph::stable_graph::StableDiGraph<GraphNode, GraphEdge>;
+
+/// Estatísticas do índice usadas no cálculo de relevância (BM25).
+#[derive(Debug, Clone, Default)]
+pub struct IndexStats {
+    /// Total de termos indexados por campo, somando todos os produtos.
+    pub field_lengths: std::collections::HashMap<ProductField, usize>,
+}
+
+impl IndexStats {
+    /// Tamanho médio do campo por produto.
+    pub fn average_field_length(&self, field: ProductField, product_count: usize) -> f64 {
+        if product_count == 0 {
+            return 0.0;
+        }
+        *self.field_lengths.get(&field).unwrap_or(&0) as f64 / product_count as f64
+    }
+}
 
 /// Estrutura principal que encapsula o Grafo e os índices de acesso rápido (Tabelas Hash).
 #[derive(Debug)]
@@ -78,4 +245,12 @@ pub struct SearchSystem {
     pub product_id_to_index: std::collections::HashMap<u32, NodeIndex>,
     /// Tabela Hash (HashMap) para mapear o ID de Cliente (externo) para o índice interno (NodeIndex). O(1) Access.
     pub user_id_to_index: std::collections::HashMap<u32, NodeIndex>,
+    /// Índice invertido de Termos (Tabela Hash + dicionário ordenado + sufixos) para o nó no grafo.
+    pub term_index: TermIndex,
+    /// Estatísticas dos campos indexados (mantidas nas atualizações incrementais).
+    pub index_stats: IndexStats,
+    /// Analisador de texto usado na indexação e nas consultas.
+    pub analyzer: std::sync::Arc<dyn Analyzer>,
+    /// Sinônimos e aliases usados para expandir os termos das consultas (vazio por padrão).
+    pub synonyms: SynonymMap,
 }
\ No newline at end of file
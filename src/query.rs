@@ -0,0 +1,378 @@
+// src/query.rs
+
+use crate::analyzer::{fold_diacritics, Analyzer};
+use crate::error::MegaStoreError;
+use crate::models::ProductField;
+use crate::search::MatchMode;
+
+/// Árvore sintática de uma consulta. Os termos já estão normalizados pelo analisador.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryNode {
+    /// Termo solto: casa por correspondência parcial, sinônimos e (se ativa) aproximação.
+    Term(String),
+    /// Frase entre aspas: termos exatos em posições consecutivas (com a posição de cada um na frase).
+    Phrase(Vec<(String, usize)>),
+    /// `campo:valor`: os termos do valor em posições consecutivas do campo indicado.
+    Field { field: ProductField, words: Vec<(String, usize)> },
+    And(Vec<QueryNode>),
+    Or(Vec<QueryNode>),
+    /// `NOT x` ou `-x`: dentro de `And`, remove do grupo os produtos que atendem `x`;
+    /// dentro de `Or`, acrescenta todos os produtos que não atendem `x`.
+    Not(Box<QueryNode>),
+}
+
+impl QueryNode {
+    /// Termos soltos e de frases fora de negações, na ordem em que aparecem.
+    pub fn positive_terms(&self) -> Vec<String> {
+        match self {
+            QueryNode::Term(term) => vec![term.clone()],
+            QueryNode::Phrase(words) => words.iter().map(|(term, _)| term.clone()).collect(),
+            QueryNode::Field { .. } | QueryNode::Not(_) => Vec::new(),
+            QueryNode::And(children) | QueryNode::Or(children) => children.iter().flat_map(QueryNode::positive_terms).collect(),
+        }
+    }
+}
+
+/// Interpreta uma consulta na linguagem de busca:
+///
+/// * termos soltos (`smart tv`), combinados pelo operador padrão `default_mode`;
+/// * frases entre aspas (`"smart tv"`);
+/// * campos: `brand:samsung`, `category:"cama mesa"`, `tag:qled`, `name:tv`
+///   (também `marca`, `categoria`, `tags` e `nome`);
+/// * operadores `AND`, `OR` e `NOT` (em maiúsculas; `AND` tem precedência sobre `OR`),
+///   `-termo` como atalho de `NOT` (um `-` entre espaços é só separador, como em
+///   `cabo usb - 2m`) e parênteses para agrupar. Termos negados soltos na
+///   consulta (`smart tv -qled`) sempre excluem, mesmo com o operador padrão `Or`.
+///
+/// `nome:valor` só é um campo quando o nome é conhecido e o valor não está vazio; nos demais
+/// casos (`4K: LG`, `12:00`) o token é tratado como palavras comuns.
+///
+/// Exemplo: `brand:samsung AND (tv OR qled) -category:vestuário`.
+///
+/// Erros de sintaxe retornam `MegaStoreError::QueryParse` com a posição do problema;
+/// uma consulta sem nenhum termo retorna `MegaStoreError::InvalidData`.
+pub fn parse_query(query: &str, analyzer: &dyn Analyzer, default_mode: MatchMode) -> Result<QueryNode, MegaStoreError> {
+    let lexemes = tokenize(query)?;
+    let mut parser = Parser { lexemes, next: 0, analyzer, default_mode };
+
+    let root = parser.parse_sequence()?;
+    if let Some(lexeme) = parser.peek() {
+        // `parse_sequence` só para antes do fim em um ')' sem par.
+        return Err(parse_error(lexeme.position, "')' sem '(' correspondente"));
+    }
+    root.ok_or_else(|| MegaStoreError::InvalidData("consulta de busca vazia".to_string()))
+}
+
+/// Palavra da consulta fora de negações, com o trecho que ocupa no texto original.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryWord {
+    /// Termo normalizado pelo analisador.
+    pub term: String,
+    /// Offset (em bytes) do início da palavra na consulta.
+    pub start: usize,
+    /// Offset (em bytes) do fim da palavra na consulta.
+    pub end: usize,
+    /// Palavra de frase ou de campo, que só casa com o termo exato.
+    pub exact: bool,
+}
+
+/// Palavras soltas, de frases e de valores de campo da consulta que não estão sob `NOT` ou `-`,
+/// na ordem em que aparecem. Usado para corrigir palavras sem reescrever o restante da consulta
+/// (nomes de campo, aspas, operadores e negações ficam como o usuário digitou).
+pub fn positive_words(query: &str, analyzer: &dyn Analyzer) -> Result<Vec<QueryWord>, MegaStoreError> {
+    let mut words = Vec::new();
+    let mut push = |text: &str, offset: usize, exact: bool| {
+        words.extend(analyzer.analyze(text).into_iter().map(|token| QueryWord {
+            term: token.term,
+            start: offset + token.start,
+            end: offset + token.end,
+            exact,
+        }));
+    };
+
+    // `negated_group` guarda a profundidade do grupo negado em que estamos, se houver.
+    let mut depth = 0;
+    let mut negated_group = None;
+    let mut negate_next = false;
+    for lexeme in tokenize(query)? {
+        let negated = std::mem::take(&mut negate_next) || negated_group.is_some();
+        match lexeme.kind {
+            TokenKind::Not | TokenKind::Minus => negate_next = true,
+            TokenKind::LeftParen => {
+                depth += 1;
+                if negated && negated_group.is_none() {
+                    negated_group = Some(depth);
+                }
+            }
+            TokenKind::RightParen => {
+                if negated_group == Some(depth) {
+                    negated_group = None;
+                }
+                depth -= 1;
+            }
+            _ if negated => {}
+            TokenKind::Word(word) => push(&word, lexeme.position, false),
+            TokenKind::Phrase(text) => push(&text, lexeme.position + 1, true),
+            TokenKind::Field { value, value_position, .. } => push(&value, value_position, true),
+            TokenKind::And | TokenKind::Or => {}
+        }
+    }
+    Ok(words)
+}
+
+// --- Funções Auxiliares (Privadas) ---
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Word(String),
+    Phrase(String),
+    /// `value_position` é o offset (em bytes) do início do valor na consulta.
+    Field { field: ProductField, name: String, value: String, value_position: usize },
+    LeftParen,
+    RightParen,
+    Minus,
+    And,
+    Or,
+    Not,
+}
+
+#[derive(Debug, Clone)]
+struct Lexeme {
+    kind: TokenKind,
+    /// Offset (em bytes) do início do token na consulta.
+    position: usize,
+}
+
+fn parse_error(position: usize, message: impl Into<String>) -> MegaStoreError {
+    MegaStoreError::QueryParse { position, message: message.into() }
+}
+
+/// Quebra a consulta em tokens. Aspas sem fechamento valem até o fim da consulta.
+fn tokenize(query: &str) -> Result<Vec<Lexeme>, MegaStoreError> {
+    let mut lexemes = Vec::new();
+    let mut chars = query.char_indices().peekable();
+
+    // Conteúdo de uma frase a partir da posição logo após a aspa de abertura.
+    let read_phrase = |chars: &mut std::iter::Peekable<std::str::CharIndices>, start: usize| -> String {
+        let mut end = query.len();
+        for (offset, c) in chars.by_ref() {
+            if c == '"' {
+                end = offset;
+                break;
+            }
+        }
+        query[start..end].to_string()
+    };
+
+    while let Some(&(position, c)) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' => {
+                chars.next();
+                let kind = if c == '(' { TokenKind::LeftParen } else { TokenKind::RightParen };
+                lexemes.push(Lexeme { kind, position });
+            }
+            '"' => {
+                chars.next();
+                let phrase = read_phrase(&mut chars, position + 1);
+                lexemes.push(Lexeme { kind: TokenKind::Phrase(phrase), position });
+            }
+            '-' => {
+                chars.next();
+                // Colado ao termo é negação; entre espaços é só um separador ("cabo usb - 2m");
+                // no fim da consulta é um erro, como um `NOT` sem termo.
+                match chars.peek() {
+                    Some(&(_, next)) if !next.is_whitespace() => lexemes.push(Lexeme { kind: TokenKind::Minus, position }),
+                    _ if query[position + 1..].trim().is_empty() => {
+                        return Err(parse_error(position, "esperado um termo após '-'"));
+                    }
+                    _ => {}
+                }
+            }
+            _ => {
+                let mut end = query.len();
+                while let Some(&(offset, next)) = chars.peek() {
+                    if next.is_whitespace() || matches!(next, '(' | ')' | '"') {
+                        end = offset;
+                        break;
+                    }
+                    chars.next();
+                }
+                let word = &query[position..end];
+
+                let kind = match word {
+                    "AND" => TokenKind::And,
+                    "OR" => TokenKind::Or,
+                    "NOT" => TokenKind::Not,
+                    _ => match word.split_once(':').and_then(|(name, value)| Some((name, field_by_name(name)?, value))) {
+                        Some((name, field, value)) if !value.is_empty() => {
+                            let value_position = position + name.len() + 1;
+                            TokenKind::Field { field, name: name.to_string(), value: value.to_string(), value_position }
+                        }
+                        Some((name, field, _)) if chars.peek().is_some_and(|&(_, next)| next == '"') => {
+                            let (quote, _) = chars.next().unwrap_or_default();
+                            let value = read_phrase(&mut chars, quote + 1);
+                            TokenKind::Field { field, name: name.to_string(), value, value_position: quote + 1 }
+                        }
+                        // Nome desconhecido ou valor vazio: palavras comuns ("4K: LG", "12:00")
+                        _ => TokenKind::Word(word.to_string()),
+                    },
+                };
+                lexemes.push(Lexeme { kind, position });
+            }
+        }
+    }
+    Ok(lexemes)
+}
+
+/// Campo do produto pelo nome usado na consulta (em inglês ou português).
+fn field_by_name(name: &str) -> Option<ProductField> {
+    match fold_diacritics(&name.to_lowercase()).as_str() {
+        "name" | "nome" => Some(ProductField::Name),
+        "brand" | "marca" => Some(ProductField::Brand),
+        "category" | "categoria" => Some(ProductField::Category),
+        "tag" | "tags" => Some(ProductField::Tags),
+        _ => None,
+    }
+}
+
+/// Parser descendente recursivo. Gramática (do menor para o maior nível de precedência):
+///
+/// ```text
+/// sequência := ou+                  (combinados pelo operador padrão)
+/// ou        := e ("OR" e)*
+/// e         := unário ("AND" unário)*
+/// unário    := ("NOT" | "-") unário | "(" sequência ")" | palavra | frase | campo
+/// ```
+///
+/// Palavras que o analisador descarta (stopwords) não geram nós, por isso os métodos
+/// retornam `Option`.
+struct Parser<'a> {
+    lexemes: Vec<Lexeme>,
+    next: usize,
+    analyzer: &'a dyn Analyzer,
+    default_mode: MatchMode,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Lexeme> {
+        self.lexemes.get(self.next)
+    }
+
+    /// Consome o próximo token se for do tipo `kind`, retornando sua posição.
+    fn eat(&mut self, kind: &TokenKind) -> Option<usize> {
+        let lexeme = self.peek().filter(|lexeme| lexeme.kind == *kind)?;
+        let position = lexeme.position;
+        self.next += 1;
+        Some(position)
+    }
+
+    fn parse_sequence(&mut self) -> Result<Option<QueryNode>, MegaStoreError> {
+        let mut nodes = Vec::new();
+        while self.peek().is_some_and(|lexeme| lexeme.kind != TokenKind::RightParen) {
+            if let Some(node) = self.parse_or()? {
+                if !nodes.contains(&node) {
+                    nodes.push(node);
+                }
+            }
+        }
+
+        // Negações soltas na sequência excluem produtos também no modo `Or`:
+        // "tv -qled" vira `And([tv, Not(qled)])`, não uma união com o complemento.
+        let (negated, positive): (Vec<QueryNode>, Vec<QueryNode>) =
+            nodes.into_iter().partition(|node| matches!(node, QueryNode::Not(_)));
+        let mut nodes: Vec<QueryNode> = combine(positive, self.default_mode).into_iter().collect();
+        nodes.extend(negated);
+        Ok(combine(nodes, MatchMode::And))
+    }
+
+    fn parse_or(&mut self) -> Result<Option<QueryNode>, MegaStoreError> {
+        let mut nodes: Vec<QueryNode> = self.parse_and(None)?.into_iter().collect();
+        while let Some(position) = self.eat(&TokenKind::Or) {
+            nodes.extend(self.parse_and(Some(("OR", position)))?);
+        }
+        Ok(combine(nodes, MatchMode::Or))
+    }
+
+    fn parse_and(&mut self, after: Option<(&str, usize)>) -> Result<Option<QueryNode>, MegaStoreError> {
+        let mut nodes: Vec<QueryNode> = self.parse_unary(after)?.into_iter().collect();
+        while let Some(position) = self.eat(&TokenKind::And) {
+            nodes.extend(self.parse_unary(Some(("AND", position)))?);
+        }
+        Ok(combine(nodes, MatchMode::And))
+    }
+
+    /// `after` é o operador que exige este operando (para a mensagem de erro).
+    fn parse_unary(&mut self, after: Option<(&str, usize)>) -> Result<Option<QueryNode>, MegaStoreError> {
+        let Some(lexeme) = self.peek().cloned() else {
+            let (operator, position) = after.unwrap_or(("", 0));
+            return Err(parse_error(position, format!("esperado um termo após '{}'", operator)));
+        };
+
+        match lexeme.kind {
+            TokenKind::RightParen | TokenKind::And | TokenKind::Or => match after {
+                Some((operator, position)) => Err(parse_error(position, format!("esperado um termo após '{}'", operator))),
+                None => Err(parse_error(lexeme.position, "operador sem termo à esquerda")),
+            },
+            TokenKind::Not | TokenKind::Minus => {
+                self.next += 1;
+                let operator = if lexeme.kind == TokenKind::Not { "NOT" } else { "-" };
+                let inner = self.parse_unary(Some((operator, lexeme.position)))?;
+                Ok(inner.map(|node| QueryNode::Not(Box::new(node))))
+            }
+            TokenKind::LeftParen => {
+                self.next += 1;
+                let group_start = self.next;
+                let inner = self.parse_sequence()?;
+                let is_empty = self.next == group_start;
+                if self.eat(&TokenKind::RightParen).is_none() {
+                    return Err(parse_error(lexeme.position, "parêntese '(' sem fechamento"));
+                }
+                match inner {
+                    Some(node) => Ok(Some(node)),
+                    None if is_empty => Err(parse_error(lexeme.position, "grupo '()' sem termos")),
+                    None => Err(parse_error(lexeme.position, "grupo entre parênteses só tem stopwords")),
+                }
+            }
+            TokenKind::Word(word) => {
+                self.next += 1;
+                let mut terms = self.analyzer.terms(&word);
+                let mut seen = std::collections::BTreeSet::new();
+                terms.retain(|term| seen.insert(term.clone()));
+                Ok(combine(terms.into_iter().map(QueryNode::Term).collect(), self.default_mode))
+            }
+            TokenKind::Phrase(text) => {
+                self.next += 1;
+                let words = self.words(&text);
+                Ok((!words.is_empty()).then_some(QueryNode::Phrase(words)))
+            }
+            TokenKind::Field { field, name, value, .. } => {
+                self.next += 1;
+                let words = self.words(&value);
+                if words.is_empty() {
+                    return Err(parse_error(lexeme.position, format!("campo '{}' sem termos pesquisáveis", name)));
+                }
+                Ok(Some(QueryNode::Field { field, words }))
+            }
+        }
+    }
+
+    /// Termos de um texto com a posição de cada um.
+    fn words(&self, text: &str) -> Vec<(String, usize)> {
+        self.analyzer.analyze(text).into_iter().map(|token| (token.term, token.position)).collect()
+    }
+}
+
+/// Junta os nós com o operador indicado (um único nó é retornado como está).
+fn combine(mut nodes: Vec<QueryNode>, mode: MatchMode) -> Option<QueryNode> {
+    match nodes.len() {
+        0 => None,
+        1 => nodes.pop(),
+        _ => Some(match mode {
+            MatchMode::And => QueryNode::And(nodes),
+            MatchMode::Or => QueryNode::Or(nodes),
+        }),
+    }
+}
@@ -1,101 +1,345 @@
-use crate::models::{SearchSystem, Product, GraphNode, EdgeType};
+use crate::error::MegaStoreError;
+use crate::models::{SearchSystem, Product, GraphNode, GraphEdge, EdgeType};
+use petgraph::graph::NodeIndex;
 use petgraph::Direction;
-use std::collections::{HashSet, HashMap};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
 use petgraph::visit::EdgeRef;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Peso de cada tipo de interação Cliente -> Produto no recomendador.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InteractionWeights {
+    pub bought: f64,
+    pub viewed: f64,
+    pub added_to_cart: f64,
+    pub wishlisted: f64,
+    /// Peso de uma avaliação com nota 5; notas menores valem proporcionalmente (nota / 5) e
+    /// notas até `MAX_NEGATIVE_RATING` não valem nada.
+    pub rated: f64,
+}
+
+/// Maior nota tratada como avaliação negativa (1 ou 2 estrelas): a aresta tem peso zero, então
+/// quem não gostou de um produto não vira vizinho de quem gostou.
+pub const MAX_NEGATIVE_RATING: u8 = 2;
+
+impl InteractionWeights {
+    /// Peso do tipo de aresta (0.0 para arestas que não são interações).
+    pub fn get(&self, kind: EdgeType) -> f64 {
+        match kind {
+            EdgeType::BOUGHT => self.bought,
+            EdgeType::VIEWED => self.viewed,
+            EdgeType::ADDED_TO_CART => self.added_to_cart,
+            EdgeType::WISHLISTED => self.wishlisted,
+            EdgeType::RATED => self.rated,
+            _ => 0.0,
+        }
+    }
+
+    /// Peso de uma aresta de interação, considerando a nota das avaliações e a quantidade comprada.
+    pub fn edge_weight(&self, edge: &GraphEdge) -> f64 {
+        let weight = self.get(edge.kind);
+        match (edge.kind, edge.rating, edge.quantity) {
+            (EdgeType::RATED, Some(rating), _) if rating <= MAX_NEGATIVE_RATING => 0.0,
+            (EdgeType::RATED, Some(rating), _) => weight * f64::from(rating) / 5.0,
+            (EdgeType::BOUGHT, _, Some(quantity)) => weight * f64::from(quantity),
+            _ => weight,
+        }
+    }
+}
+
+impl Default for InteractionWeights {
+    fn default() -> Self {
+        InteractionWeights { bought: 5.0, viewed: 1.0, added_to_cart: 3.0, wishlisted: 2.0, rated: 4.0 }
+    }
+}
+
+/// Como medir a semelhança entre o cliente e cada vizinho.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NeighborSimilarity {
+    /// Produto escalar dos vetores de interação ponderados (soma, nos produtos em comum,
+    /// de `peso do cliente * peso do vizinho`). Favorece vizinhos muito ativos.
+    Overlap,
+    /// Produtos em comum divididos pelos produtos de qualquer um dos dois (ignora os pesos).
+    Jaccard,
+    /// Cosseno entre os vetores de interação ponderados (padrão).
+    #[default]
+    Cosine,
+}
+
+/// Quantidade padrão de vizinhos mais semelhantes considerados em cada recomendação.
+pub const DEFAULT_MAX_NEIGHBORS: usize = 50;
+
+/// Peso padrão dos produtos ligados por tags ao histórico do cliente.
+pub const DEFAULT_TAG_RELATION_WEIGHT: f64 = 0.1;
+
+/// Parâmetros de uma recomendação.
+#[derive(Debug, Clone)]
+pub struct RecommendOptions {
+    pub weights: InteractionWeights,
+    pub similarity: NeighborSimilarity,
+    /// Apenas os `max_neighbors` vizinhos mais semelhantes contribuem (`None` = todos).
+    pub max_neighbors: Option<usize>,
+    /// Peso dos produtos ligados por arestas `RELATED_BY_TAG` aos produtos do histórico
+    /// (0.0 desliga). Complementa o filtro colaborativo, inclusive para clientes sem vizinhos.
+    pub tag_relation_weight: f64,
+    /// Quantidade máxima de produtos recomendados.
+    pub limit: usize,
+    /// Meia-vida do decaimento temporal: uma interação com essa idade vale metade.
+    /// `None` desliga o decaimento; interações sem timestamp nunca decaem.
+    pub half_life: Option<Duration>,
+    /// Momento de referência (timestamp Unix, em segundos) para calcular a idade das
+    /// interações. `None` usa o relógio do sistema.
+    pub now: Option<u64>,
+}
+
+impl RecommendOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_weights(mut self, weights: InteractionWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    pub fn with_similarity(mut self, similarity: NeighborSimilarity) -> Self {
+        self.similarity = similarity;
+        self
+    }
+
+    pub fn with_max_neighbors(mut self, max_neighbors: Option<usize>) -> Self {
+        self.max_neighbors = max_neighbors;
+        self
+    }
+
+    pub fn with_tag_relation_weight(mut self, tag_relation_weight: f64) -> Self {
+        self.tag_relation_weight = tag_relation_weight;
+        self
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    pub fn with_half_life(mut self, half_life: Duration) -> Self {
+        self.half_life = Some(half_life);
+        self
+    }
+
+    pub fn with_now(mut self, now: u64) -> Self {
+        self.now = Some(now);
+        self
+    }
+}
+
+impl Default for RecommendOptions {
+    fn default() -> Self {
+        RecommendOptions {
+            weights: InteractionWeights::default(),
+            similarity: NeighborSimilarity::default(),
+            max_neighbors: Some(DEFAULT_MAX_NEIGHBORS),
+            tag_relation_weight: DEFAULT_TAG_RELATION_WEIGHT,
+            limit: 5,
+            half_life: None,
+            now: None,
+        }
+    }
+}
 
 /// Encontra recomendações de produtos para um usuário específico.
-/// 
+///
 /// Algoritmo Simplificado: Recomendação Baseada em Clientes Vizinhos (Collaborative Filtering).
+/// Usuários inexistentes resultam em uma lista vazia; use `try_recommend_for_user` para tratar o erro.
 pub fn recommend_for_user(system: &SearchSystem, user_id: u32) -> Vec<Product> {
+    match try_recommend_for_user(system, user_id) {
+        Ok(recommendations) => recommendations,
+        Err(err) => {
+            log::warn!(user_id = user_id, error:% = err; "Não foi possível gerar recomendações");
+            Vec::new()
+        }
+    }
+}
+
+/// Versão de `recommend_for_user` que retorna `MegaStoreError::UnknownUser` em vez de uma lista vazia
+/// quando o usuário não está indexado.
+pub fn try_recommend_for_user(system: &SearchSystem, user_id: u32) -> Result<Vec<Product>, MegaStoreError> {
+    let recommendations = recommend_with_options(system, user_id, &RecommendOptions::default())?;
+    Ok(recommendations.into_iter().map(|(product, _)| product).collect())
+}
+
+/// Recomendações com pesos por tipo de interação, cada produto com sua pontuação.
+///
+/// 1. O histórico do cliente vira um vetor: por produto, a soma dos pesos das suas interações.
+/// 2. Cada cliente vizinho (que interagiu com algum produto do histórico) recebe uma
+///    semelhança com o cliente (`options.similarity`), calculada sobre o vetor de interações
+///    do vizinho; apenas os `max_neighbors` mais semelhantes seguem adiante.
+/// 3. Cada produto fora do histórico soma `semelhança do vizinho * peso da interação do vizinho`.
+/// 4. Produtos ligados por tags a um produto do histórico somam
+///    `tag_relation_weight * peso no histórico * peso da aresta RELATED_BY_TAG`.
+///
+/// Com `half_life`, o peso de cada interação é multiplicado por `0.5^(idade / meia-vida)`,
+/// então a visualização da semana passada pode valer mais que a compra do ano passado.
+///
+/// Os resultados são ordenados pela pontuação (e pelo ID em caso de empate) e limitados a `options.limit`.
+pub fn recommend_with_options(
+    system: &SearchSystem,
+    user_id: u32,
+    options: &RecommendOptions,
+) -> Result<Vec<(Product, f64)>, MegaStoreError> {
     let graph = &system.graph;
-    
+    let started = Instant::now();
+    let scorer = InteractionScorer::new(options);
+
     // 1. Encontrar o índice do usuário de partida (Usando o HashMap O(1))
-    let user_index = match system.user_id_to_index.get(&user_id) {
-        Some(index) => *index,
-        None => {
-            println!("\nUsuário ID {} não encontrado no sistema.", user_id);
-            return Vec::new();
-        }
-    };
-    println!("\n--- Gerando Recomendações para Usuário ID: {} ---", user_id);
-
-    // Conjunto para armazenar produtos que o usuário JÁ viu/comprou
-    let mut user_history = HashSet::new();
-    // Mapa para armazenar produtos que os vizinhos interagiram, e a contagem de vizinhos
-    let mut neighbor_product_counts: HashMap<u32, i32> = HashMap::new();
-    
-    // 2. Encontrar o histórico do usuário (Produtos que ele interagiu)
-    for edge in graph.edges_directed(user_index, Direction::Outgoing) {
-        let neighbor_index = edge.target();
-        // Arestas de interesse: BOUGHT ou VIEWED
-        if edge.weight() == &EdgeType::BOUGHT || edge.weight() == &EdgeType::VIEWED {
-             if let Some(GraphNode::Product(product)) = graph.node_weight(neighbor_index) {
-                user_history.insert(product.id);
+    let user_index = *system.user_id_to_index.get(&user_id)
+        .ok_or(MegaStoreError::UnknownUser(user_id))?;
+    log::debug!(user_id = user_id; "Gerando recomendações");
+
+    // 2. Histórico do usuário: produtos com que ele interagiu e o peso somado das interações
+    let user_history = interaction_vector(system, user_index, &scorer);
+
+    // 3. Encontrar Clientes Vizinhos (outros clientes que interagiram com produtos do histórico)
+    // e a semelhança de cada um com o usuário, sobre os vetores de interação completos
+    let mut neighbor_indices: HashSet<NodeIndex> = HashSet::new();
+    for &product_index in user_history.keys() {
+        for edge in graph.edges_directed(product_index, Direction::Incoming) {
+            let neighbor_index = edge.source();
+            if neighbor_index != user_index && edge.weight().kind.is_interaction() {
+                neighbor_indices.insert(neighbor_index);
             }
         }
     }
-    
-    // 3. Encontrar Clientes Vizinhos (Vizinhos de Produtos comprados/vistos pelo Cliente A)
-    // Para simplificar, vamos encontrar vizinhos de produtos no histórico
-    for &product_id in &user_history {
-        let product_index = *system.product_id_to_index.get(&product_id).unwrap();
 
-        // Encontrar vizinhos do PRODUTO (outros usuários que o compraram)
-        for edge in graph.edges_directed(product_index, Direction::Incoming) {
-            let neighbor_index = edge.source();
+    let mut neighbors: Vec<(NodeIndex, f64, HashMap<NodeIndex, f64>)> = neighbor_indices
+        .into_iter()
+        .filter_map(|neighbor_index| {
+            let vector = interaction_vector(system, neighbor_index, &scorer);
+            let similarity = match options.similarity {
+                NeighborSimilarity::Overlap => dot_product(&user_history, &vector),
+                NeighborSimilarity::Jaccard => jaccard(&user_history, &vector),
+                NeighborSimilarity::Cosine => cosine(&user_history, &vector),
+            };
+            (similarity > 0.0).then_some((neighbor_index, similarity, vector))
+        })
+        .collect();
+
+    // Top-K vizinhos mais semelhantes (índice do nó como desempate estável)
+    neighbors.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    if let Some(max_neighbors) = options.max_neighbors {
+        neighbors.truncate(max_neighbors);
+    }
+
+    // 4. Pontuar os produtos dos vizinhos que o usuário ainda não conhece
+    let mut candidate_scores: HashMap<NodeIndex, f64> = HashMap::new();
+    for (_, similarity, vector) in &neighbors {
+        for (&product_index, &weight) in vector {
+            if weight > 0.0 && !user_history.contains_key(&product_index) {
+                *candidate_scores.entry(product_index).or_insert(0.0) += similarity * weight;
+            }
+        }
+    }
 
-            // Se o vizinho é um Usuário (e não o próprio User A)
-            if let Some(GraphNode::User(neighbor_user)) = graph.node_weight(neighbor_index) {
-                if neighbor_user.id != user_id {
-                    
-                    // 4. Encontrar os produtos que esses vizinhos compraram/viram
-                    for neighbor_edge in graph.edges_directed(neighbor_index, Direction::Outgoing) {
-                        let recommended_product_index = neighbor_edge.target();
-                        
-                        // Garante que é uma interação BOUGHT/VIEWED e que o nó é um Produto
-                        if (neighbor_edge.weight() == &EdgeType::BOUGHT || neighbor_edge.weight() == &EdgeType::VIEWED) 
-                           && graph.node_weight(recommended_product_index).map(|n| n.get_type()) == Some(crate::models::NodeType::Product)
-                        {
-                            if let Some(GraphNode::Product(rec_product)) = graph.node_weight(recommended_product_index) {
-                                // 5. Filtrar: Só recomenda se o User A não tiver visto/comprado
-                                if !user_history.contains(&rec_product.id) {
-                                    // Aumenta a contagem de relevância
-                                    *neighbor_product_counts.entry(rec_product.id).or_insert(0) += 1;
-                                }
-                            }
-                        }
-                    }
+    // 5. Produtos ligados por tags aos produtos do histórico
+    if options.tag_relation_weight > 0.0 {
+        for (&product_index, &history_weight) in user_history.iter().filter(|(_, weight)| **weight > 0.0) {
+            for edge in graph.edges_directed(product_index, Direction::Outgoing) {
+                let related_index = edge.target();
+                if edge.weight().kind == EdgeType::RELATED_BY_TAG && !user_history.contains_key(&related_index) {
+                    let relation = edge.weight().weight.unwrap_or(0.0);
+                    *candidate_scores.entry(related_index).or_insert(0.0) += options.tag_relation_weight * history_weight * relation;
                 }
             }
         }
     }
 
-   // 6. Converter resultados SEM USAR into_iter() para preservar o HashMap
-    let mut recommendations_temp: Vec<(Product, i32)> = neighbor_product_counts.iter()
-        .map(|(id, count)| {
-            // Buscamos o objeto Product real
-            let index = system.product_id_to_index.get(id).unwrap();
-            if let Some(GraphNode::Product(p)) = graph.node_weight(*index) {
-                println!("  [Relevância: {}] -> {} (ID: {})", count, p.name, p.id);
-                (p.clone(), *count) // Retorna o Produto e a contagem de relevância
-            } else {
-                unreachable!() // Não deve acontecer
+    // 6. Converter para produtos, ordenar pela pontuação (ID como desempate estável) e limitar
+    let mut recommendations: Vec<(Product, f64)> = candidate_scores
+        .into_iter()
+        .filter_map(|(product_index, score)| match graph.node_weight(product_index) {
+            Some(GraphNode::Product(product)) => {
+                log::trace!(user_id = user_id, product_id = product.id, relevance = score; "Candidato a recomendação");
+                Some((product.clone(), score))
             }
+            _ => None,
         })
         .collect();
-        
-    // 7. Ordenar de forma decrescente pela contagem (o elemento `count` está na tupla)
-    // Usamos `sort_by` porque é mais fácil para ordenar tuplas.
-    recommendations_temp.sort_by(|a, b| b.1.cmp(&a.1)); // Compara o count (o segundo elemento da tupla)
-
-    recommendations_temp.truncate(5); // Limita as 5 melhores recomendações
-    
-    // Converte de volta para Vec<Product>
-    let recommendations: Vec<Product> = recommendations_temp.into_iter().map(|(p, _)| p).collect();
-
-    if recommendations.is_empty() {
-        println!("  Nenhuma recomendação nova baseada em vizinhos encontrada.");
-    }
-    
-    recommendations
-}
\ No newline at end of file
+    recommendations.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.id.cmp(&b.0.id)));
+    recommendations.truncate(options.limit);
+
+    log::info!(
+        user_id = user_id,
+        result_count = recommendations.len(),
+        elapsed_us = started.elapsed().as_micros() as u64;
+        "Recomendações geradas"
+    );
+
+    Ok(recommendations)
+}
+
+/// Produto escalar de dois vetores esparsos.
+pub(crate) fn dot_product<K: Eq + Hash>(a: &HashMap<K, f64>, b: &HashMap<K, f64>) -> f64 {
+    let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    small.iter().filter_map(|(key, value)| large.get(key).map(|other| value * other)).sum()
+}
+
+/// Cosseno entre dois vetores esparsos (0.0 se algum deles for nulo).
+pub(crate) fn cosine<K: Eq + Hash>(a: &HashMap<K, f64>, b: &HashMap<K, f64>) -> f64 {
+    let norm = |vector: &HashMap<K, f64>| vector.values().map(|value| value * value).sum::<f64>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms > 0.0 { dot_product(a, b) / norms } else { 0.0 }
+}
+
+/// Índice de Jaccard entre as chaves de valor positivo de dois vetores esparsos (os valores em
+/// si são ignorados; chaves com peso zero, como avaliações negativas, não contam).
+pub(crate) fn jaccard<K: Eq + Hash>(a: &HashMap<K, f64>, b: &HashMap<K, f64>) -> f64 {
+    let present = |vector: &HashMap<K, f64>| vector.values().filter(|value| **value > 0.0).count();
+    let shared = a.iter().filter(|(key, value)| **value > 0.0 && b.get(*key).is_some_and(|other| *other > 0.0)).count();
+    let union = present(a) + present(b) - shared;
+    if union > 0 { shared as f64 / union as f64 } else { 0.0 }
+}
+
+// --- Funções Auxiliares (Privadas) ---
+
+/// Vetor de interações de um cliente: por produto, a soma dos pesos das suas interações.
+fn interaction_vector(system: &SearchSystem, user_index: NodeIndex, scorer: &InteractionScorer) -> HashMap<NodeIndex, f64> {
+    let mut vector: HashMap<NodeIndex, f64> = HashMap::new();
+    for edge in system.graph.edges_directed(user_index, Direction::Outgoing) {
+        if edge.weight().kind.is_interaction() {
+            *vector.entry(edge.target()).or_insert(0.0) += scorer.edge_weight(edge.weight());
+        }
+    }
+    vector
+}
+
+/// Peso efetivo das arestas de interação: peso do tipo, nota, quantidade e decaimento temporal.
+struct InteractionScorer<'a> {
+    weights: &'a InteractionWeights,
+    /// Meia-vida em segundos e momento de referência, quando o decaimento está ativo.
+    decay: Option<(f64, u64)>,
+}
+
+impl<'a> InteractionScorer<'a> {
+    fn new(options: &'a RecommendOptions) -> Self {
+        let decay = options.half_life.filter(|half_life| !half_life.is_zero()).map(|half_life| {
+            let now = options.now.unwrap_or_else(|| {
+                SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or(0)
+            });
+            (half_life.as_secs_f64(), now)
+        });
+        InteractionScorer { weights: &options.weights, decay }
+    }
+
+    fn edge_weight(&self, edge: &GraphEdge) -> f64 {
+        let weight = self.weights.edge_weight(edge);
+        match (self.decay, edge.timestamp) {
+            (Some((half_life, now)), Some(timestamp)) => {
+                // Interações "no futuro" (relógios dessincronizados) não ganham bônus.
+                let age = now.saturating_sub(timestamp) as f64;
+                weight * 0.5_f64.powf(age / half_life)
+            }
+            _ => weight,
+        }
+    }
+}
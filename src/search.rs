@@ -1,54 +1,844 @@
-use crate::models::{SearchSystem, Product, GraphNode, EdgeType};
-use std::collections::HashSet;
-use petgraph::visit::Bfs;
+use crate::analyzer::fold_diacritics;
+use crate::error::MegaStoreError;
+use crate::fuzzy::{fuzzy_matches, spelling_suggestions};
+use crate::explain::{Boost, BoostKind, Explanation, MatchKind, TermMatch};
+use crate::highlight::{find_highlights, Highlights};
+use crate::query::{parse_query, QueryNode};
+use crate::models::{SearchSystem, Product, GraphNode, EdgeType, ProductField};
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Instant;
 
-/// Encontra produtos relevantes com base em um termo de busca, usando BFS no grafo.
+/// Como combinar os termos de uma consulta com várias palavras.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchMode {
+    /// Todos os termos da consulta devem corresponder ao produto.
+    And,
+    /// Basta um termo corresponder (padrão).
+    #[default]
+    Or,
+}
+
+/// Ordem dos resultados. Todas desempatam pelo ID do produto, para que as páginas
+/// sejam estáveis entre requisições.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortOrder {
+    /// Termos atendidos e pontuação BM25 (padrão).
+    #[default]
+    Relevance,
+    /// Nome do produto em ordem alfabética (sem diferenciar maiúsculas e acentos).
+    Name,
+    /// Mais comprados primeiro (arestas `BOUGHT`), depois relevância.
+    Popularity,
+    /// Cadastrados mais recentemente primeiro (`Product::created_at`); sem data vão para o fim.
+    Newest,
+}
+
+/// Peso de cada campo do produto no cálculo de relevância.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldWeights {
+    pub name: f64,
+    pub tags: f64,
+    pub brand: f64,
+    pub category: f64,
+}
+
+impl FieldWeights {
+    pub fn get(&self, field: ProductField) -> f64 {
+        match field {
+            ProductField::Name => self.name,
+            ProductField::Tags => self.tags,
+            ProductField::Brand => self.brand,
+            ProductField::Category => self.category,
+        }
+    }
+}
+
+impl Default for FieldWeights {
+    fn default() -> Self {
+        FieldWeights { name: 2.0, tags: 1.5, brand: 1.5, category: 0.5 }
+    }
+}
+
+/// Parâmetros do BM25: `k1` controla a saturação da frequência do termo e `b` a
+/// normalização pelo tamanho do campo.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bm25Params {
+    pub k1: f64,
+    pub b: f64,
+}
+
+impl Default for Bm25Params {
+    fn default() -> Self {
+        Bm25Params { k1: 1.2, b: 0.75 }
+    }
+}
+
+/// Quantidade máxima de sugestões "Você quis dizer...?" em uma resposta sem resultados.
+pub const MAX_SPELLING_SUGGESTIONS: usize = 3;
+
+/// Fator aplicado à pontuação de um termo encontrado por aproximação, para cada edição de distância.
+pub const FUZZY_DISTANCE_PENALTY: f64 = 0.5;
+
+/// Fator aplicado à pontuação de um termo que só contém a consulta ou está contido nela, para
+/// que fique abaixo da correspondência exata e de um sinônimo.
+pub const PARTIAL_MATCH_PENALTY: f64 = 0.5;
+
+/// Fator aplicado à pontuação de um Termo alcançado por sinônimo, para que fique logo abaixo
+/// da correspondência exata.
+pub const SYNONYM_PENALTY: f64 = 0.8;
+
+/// Bônus padrão de proximidade: produtos com termos vizinhos da consulta também vizinhos
+/// no mesmo campo têm a pontuação multiplicada por `1 + PROXIMITY_BOOST / distância`.
+pub const PROXIMITY_BOOST: f64 = 0.5;
+
+/// Filtros opcionais aplicados aos resultados. A comparação passa pelo analisador,
+/// então "eletronicos" filtra a categoria "Eletrônicos".
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SearchFilters {
+    pub brand: Option<String>,
+    pub category: Option<String>,
+    pub tag: Option<String>,
+}
+
+/// Contagem de produtos por marca e por categoria, para montar as barras de filtro.
+///
+/// Cada faceta ignora o próprio filtro (a contagem de marcas considera os filtros de
+/// categoria e tag, mas não o de marca), para que a página mostre as outras opções.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Facets {
+    pub brands: BTreeMap<String, usize>,
+    pub categories: BTreeMap<String, usize>,
+}
+
+/// Parâmetros de uma busca.
+#[derive(Debug, Clone)]
+pub struct SearchRequest {
+    pub query: String,
+    pub mode: MatchMode,
+    pub field_weights: FieldWeights,
+    pub bm25: Bm25Params,
+    /// Tolera erros de digitação ("samsumg", "jens") expandindo cada termo para os
+    /// Termos do dicionário a poucas edições de distância.
+    pub fuzzy: bool,
+    /// Expande os termos da consulta pelo dicionário de sinônimos do sistema (`SearchSystem::synonyms`).
+    pub synonyms: bool,
+    /// Bônus para termos da consulta próximos no produto (0.0 desliga). Veja `PROXIMITY_BOOST`.
+    pub proximity_boost: f64,
+    /// Preenche `SearchHit::explanation` com o detalhamento da pontuação de cada resultado.
+    pub explain: bool,
+    pub filters: SearchFilters,
+    pub sort: SortOrder,
+    /// Quantidade de resultados pulados (paginação).
+    pub offset: usize,
+    /// Tamanho máximo da página; `None` retorna todos os resultados a partir de `offset`.
+    pub limit: Option<usize>,
+}
+
+impl SearchRequest {
+    pub fn new(query: impl Into<String>) -> Self {
+        SearchRequest {
+            query: query.into(),
+            mode: MatchMode::default(),
+            field_weights: FieldWeights::default(),
+            bm25: Bm25Params::default(),
+            fuzzy: false,
+            synonyms: true,
+            proximity_boost: PROXIMITY_BOOST,
+            explain: false,
+            filters: SearchFilters::default(),
+            sort: SortOrder::default(),
+            offset: 0,
+            limit: None,
+        }
+    }
+
+    pub fn with_mode(mut self, mode: MatchMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_field_weights(mut self, field_weights: FieldWeights) -> Self {
+        self.field_weights = field_weights;
+        self
+    }
+
+    pub fn with_bm25(mut self, bm25: Bm25Params) -> Self {
+        self.bm25 = bm25;
+        self
+    }
+
+    pub fn with_fuzzy(mut self, fuzzy: bool) -> Self {
+        self.fuzzy = fuzzy;
+        self
+    }
+
+    pub fn with_synonyms(mut self, synonyms: bool) -> Self {
+        self.synonyms = synonyms;
+        self
+    }
+
+    pub fn with_proximity_boost(mut self, proximity_boost: f64) -> Self {
+        self.proximity_boost = proximity_boost;
+        self
+    }
+
+    pub fn with_explain(mut self, explain: bool) -> Self {
+        self.explain = explain;
+        self
+    }
+
+    pub fn with_brand(mut self, brand: impl Into<String>) -> Self {
+        self.filters.brand = Some(brand.into());
+        self
+    }
+
+    pub fn with_category(mut self, category: impl Into<String>) -> Self {
+        self.filters.category = Some(category.into());
+        self
+    }
+
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.filters.tag = Some(tag.into());
+        self
+    }
+
+    pub fn with_sort(mut self, sort: SortOrder) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// Página de resultados: pula `offset` e retorna no máximo `limit`.
+    pub fn with_page(mut self, offset: usize, limit: usize) -> Self {
+        self.offset = offset;
+        self.limit = Some(limit);
+        self
+    }
+}
+
+/// Um produto encontrado, quantos termos da consulta ele atendeu e sua pontuação BM25.
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub product: Product,
+    pub matched_terms: usize,
+    pub score: f64,
+    /// Trechos do nome e das tags que casaram com a consulta (veja `highlight::highlight`).
+    pub highlights: Highlights,
+    /// Detalhamento da pontuação (apenas com `SearchRequest::explain`).
+    pub explanation: Option<Explanation>,
+}
+
+/// Resultado de uma busca, já ordenado por relevância.
+#[derive(Debug, Clone, Default)]
+pub struct SearchResponse {
+    /// Página de resultados solicitada.
+    pub hits: Vec<SearchHit>,
+    /// Total de resultados antes da paginação.
+    pub total_hits: usize,
+    /// Consultas corrigidas sugeridas quando a busca não encontra nada ("Você quis dizer: samsung?").
+    pub suggestions: Vec<String>,
+    pub facets: Facets,
+}
+
+/// Encontra produtos relevantes com base em um termo de busca, navegando pelas arestas de indexação do grafo.
+/// Consultas inválidas resultam em uma lista vazia; use `try_search_by_term` para tratar o erro.
 pub fn search_by_term(system: &SearchSystem, query: &str) -> Vec<Product> {
+    match try_search_by_term(system, query) {
+        Ok(products) => products,
+        Err(err) => {
+            log::warn!(query = query, error:% = err; "Não foi possível executar a busca");
+            Vec::new()
+        }
+    }
+}
+
+/// Versão de `search_by_term` que retorna o erro (`InvalidData` para consultas vazias,
+/// `QueryParse` para erros de sintaxe) em vez de uma lista vazia.
+pub fn try_search_by_term(system: &SearchSystem, query: &str) -> Result<Vec<Product>, MegaStoreError> {
+    let response = search(system, &SearchRequest::new(query))?;
+    Ok(response.hits.into_iter().map(|hit| hit.product).collect())
+}
+
+/// Busca simples que retorna cada produto com sua pontuação de relevância (BM25).
+pub fn search_scored(system: &SearchSystem, query: &str) -> Result<Vec<(Product, f64)>, MegaStoreError> {
+    let response = search(system, &SearchRequest::new(query))?;
+    Ok(response.hits.into_iter().map(|hit| (hit.product, hit.score)).collect())
+}
+
+/// Executa uma busca com várias palavras.
+///
+/// A consulta é interpretada por `query::parse_query`: além de termos soltos e frases, aceita
+/// campos (`brand:samsung`), operadores `AND`/`OR`/`NOT` (ou `-termo`) e parênteses. Erros de
+/// sintaxe retornam `MegaStoreError::QueryParse`.
+///
+/// A consulta é quebrada em termos da mesma forma que os nomes dos produtos são indexados.
+/// Cada termo é resolvido no índice invertido e segue as arestas `CONTAINS_TERM` até os
+/// produtos. A relevância é calculada com BM25 sobre essas arestas: a frequência do termo
+/// vem das arestas paralelas (uma por ocorrência, com peso por campo) e o IDF da quantidade
+/// de produtos alcançados pelo nó de Termo.
+///
+/// Com `fuzzy` ativo, cada termo também é expandido para Termos a poucas edições de distância
+/// (Damerau-Levenshtein); esses casamentos valem `FUZZY_DISTANCE_PENALTY` a cada edição.
+/// Os sinônimos do dicionário do sistema também são buscados, valendo `SYNONYM_PENALTY`.
+///
+/// Trechos entre aspas (`"smart tv"`) são frases: o produto precisa ter os termos exatos nas
+/// posições consecutivas de um mesmo campo, e a frase conta como um único termo atendido.
+/// Fora das aspas, termos vizinhos da consulta que também aparecem próximos no produto
+/// recebem o bônus de proximidade (`proximity_boost`).
+///
+/// Os resultados são ordenados pela quantidade de termos atendidos, depois pela pontuação
+/// e pelo ID em caso de empate. No modo `And`, só entram produtos que atendem todos os termos.
+/// Os filtros de marca, categoria e tag são aplicados por último, junto com o cálculo das facetas.
+/// A ordem pode ser trocada com `SortOrder` e o resultado paginado com `offset`/`limit`.
+pub fn search(system: &SearchSystem, request: &SearchRequest) -> Result<SearchResponse, MegaStoreError> {
     let graph = &system.graph;
-    let query = query.to_lowercase();
-    let mut results_indices = HashSet::new();
+    let started = Instant::now();
+    let query = request.query.trim();
 
-    println!("\n--- Executando Busca por Termo: '{}' ---", query);
+    // Árvore da consulta (mesmo analisador da indexação); termos soltos combinados por `request.mode`
+    let root = parse_query(query, system.analyzer.as_ref(), request.mode)?;
+    let query_terms = root.positive_terms();
 
-    // 1. Encontrar o(s) nó(s) de Termo de Busca relevantes
-    let start_indices: Vec<_> = graph.node_indices()
-        .filter(|index| {
-            if let Some(GraphNode::Term(term)) = graph.node_weight(*index) {
-                query.contains(&term.term) || term.term.contains(&query)
-            } else {
-                false
+    log::debug!(query = query, terms = query_terms.len(); "Executando busca por termo");
+
+    // 1. Para cada termo da consulta, encontrar o(s) nó(s) de Termo pelo índice invertido,
+    // 2. seguir as arestas de indexação (Termo -> Produto) até os produtos e
+    // 3. combinar os conjuntos de produtos segundo os operadores da consulta.
+    let mut evaluator = QueryEvaluator::new(system, request);
+    let matches = evaluator.evaluate(&root);
+
+    let mut hits: Vec<SearchHit> = matches
+        .into_iter()
+        .filter_map(|(product_node, product_match)| match graph.node_weight(product_node) {
+            Some(GraphNode::Product(product)) => {
+                let proximity = proximity_factor(system, product_node, &evaluator.term_candidates, request.proximity_boost);
+                let score = product_match.score * proximity;
+                let explanation = request.explain.then(|| Explanation {
+                    product_id: product.id,
+                    matched_terms: product_match.matched,
+                    base_score: product_match.score,
+                    boosts: if proximity != 1.0 { vec![Boost { kind: BoostKind::Proximity, factor: proximity }] } else { Vec::new() },
+                    score,
+                    terms: product_match.details,
+                    filters: request.filters.clone(),
+                });
+                Some(SearchHit {
+                    product: product.clone(),
+                    matched_terms: product_match.matched,
+                    score,
+                    highlights: Highlights::default(),
+                    explanation,
+                })
             }
+            _ => None,
         })
         .collect();
 
-    if start_indices.is_empty() {
-        println!("Nenhum termo de busca encontrado no grafo para '{}'.", query);
-        return Vec::new();
+    // 4. Facetas (sobre os resultados da consulta) e filtros de marca/categoria/tag
+    let filter = FilterMatcher::new(system, &request.filters);
+    let mut facets = Facets::default();
+    for hit in &hits {
+        let (brand_ok, category_ok, tag_ok) = filter.check(&hit.product);
+        if category_ok && tag_ok {
+            *facets.brands.entry(hit.product.brand.clone()).or_insert(0) += 1;
+        }
+        if brand_ok && tag_ok {
+            *facets.categories.entry(hit.product.category.clone()).or_insert(0) += 1;
+        }
+    }
+    hits.retain(|hit| filter.matches(&hit.product));
+
+    // 5. Ordenação (sempre com o ID como desempate estável) e paginação
+    sort_hits(system, &mut hits, request.sort);
+    let total_hits = hits.len();
+    let mut hits: Vec<SearchHit> = hits
+        .into_iter()
+        .skip(request.offset)
+        .take(request.limit.unwrap_or(usize::MAX))
+        .collect();
+    for hit in &mut hits {
+        hit.highlights = find_highlights(system.analyzer.as_ref(), &hit.product, &evaluator.highlight_terms);
+    }
+
+    // 6. Sem resultados: sugerir correções a partir do dicionário de Termos
+    let suggestions = if total_hits == 0 {
+        spelling_suggestions(system, query, MAX_SPELLING_SUGGESTIONS)
+    } else {
+        Vec::new()
+    };
+
+    log::info!(
+        query = query,
+        result_count = total_hits,
+        suggestion_count = suggestions.len(),
+        elapsed_us = started.elapsed().as_micros() as u64;
+        "Busca finalizada"
+    );
+
+    Ok(SearchResponse { hits, total_hits, suggestions, facets })
+}
+
+// --- Funções Auxiliares (Privadas) ---
+
+/// Quantos termos da consulta um produto atendeu, sua pontuação e (com `explain`) de onde ela veio.
+#[derive(Debug, Default)]
+struct ProductMatch {
+    matched: usize,
+    score: f64,
+    details: Vec<TermMatch>,
+}
+
+impl ProductMatch {
+    fn merge(&mut self, other: ProductMatch) {
+        self.matched += other.matched;
+        self.score += other.score;
+        self.details.extend(other.details);
+    }
+}
+
+/// Produtos atendidos por um nó da consulta.
+type Matches = HashMap<NodeIndex, ProductMatch>;
+
+/// Avalia a árvore da consulta sobre o índice de Termos e as arestas do grafo.
+struct QueryEvaluator<'a> {
+    system: &'a SearchSystem,
+    request: &'a SearchRequest,
+    scorer: Bm25Scorer<'a>,
+    /// Nós de Termo de cada termo solto fora de negações, em ordem, para o bônus de proximidade.
+    term_candidates: Vec<Vec<NodeIndex>>,
+    /// Termos fora de negações (com o campo a que foram restritos), para o destaque dos resultados.
+    highlight_terms: HashSet<(Option<ProductField>, String)>,
+    /// Profundidade de `NOT` em que a avaliação está.
+    negation_depth: usize,
+}
+
+impl<'a> QueryEvaluator<'a> {
+    fn new(system: &'a SearchSystem, request: &'a SearchRequest) -> Self {
+        QueryEvaluator {
+            system,
+            request,
+            scorer: Bm25Scorer::new(system, request),
+            term_candidates: Vec::new(),
+            highlight_terms: HashSet::new(),
+            negation_depth: 0,
+        }
+    }
+
+    fn evaluate(&mut self, node: &QueryNode) -> Matches {
+        match node {
+            QueryNode::Term(query_term) => self.evaluate_term(query_term),
+            QueryNode::Phrase(words) => {
+                self.add_highlight_terms(None, words);
+                self.evaluate_phrase(words, None)
+            }
+            QueryNode::Field { field, words } => {
+                self.add_highlight_terms(Some(*field), words);
+                self.evaluate_phrase(words, Some(*field))
+            }
+            QueryNode::And(children) => self.combine(children, MatchMode::And),
+            QueryNode::Or(children) => self.combine(children, MatchMode::Or),
+            QueryNode::Not(_) => self.combine(std::slice::from_ref(node), MatchMode::And),
+        }
+    }
+
+    /// Um termo pode corresponder a vários Termos (parcial, sinônimo ou aproximado):
+    /// cada produto fica com a melhor pontuação entre eles.
+    fn evaluate_term(&mut self, query_term: &str) -> Matches {
+        let candidates = candidate_terms(self.system, self.request, query_term);
+        if self.negation_depth == 0 {
+            self.term_candidates.push(candidates.iter().map(|(node, _, _)| *node).collect());
+            for (term_node, _, _) in &candidates {
+                if let Some(GraphNode::Term(term)) = self.system.graph.node_weight(*term_node) {
+                    self.highlight_terms.insert((None, term.term.clone()));
+                }
+            }
+        }
+
+        // Melhor candidato de cada produto: (pontuação com fator, nó de Termo, BM25, fator, tipo)
+        let mut best_per_product: HashMap<NodeIndex, (f64, NodeIndex, f64, f64, MatchKind)> = HashMap::new();
+        for (term_node, penalty, kind) in candidates {
+            for (product_node, bm25) in self.scorer.score_term(term_node) {
+                let score = bm25 * penalty;
+                let best = best_per_product.entry(product_node).or_insert((score, term_node, bm25, penalty, kind));
+                if score > best.0 {
+                    *best = (score, term_node, bm25, penalty, kind);
+                }
+            }
+        }
+
+        best_per_product
+            .into_iter()
+            .map(|(product_node, (score, term_node, bm25, penalty, kind))| {
+                let details = self.details(query_term, term_node, product_node, kind, bm25, penalty);
+                (product_node, ProductMatch { matched: 1, score, details })
+            })
+            .collect()
+    }
+
+    fn evaluate_phrase(&mut self, words: &[(String, usize)], field: Option<ProductField>) -> Matches {
+        let query_text = words.iter().map(|(term, _)| term.as_str()).collect::<Vec<_>>().join(" ");
+        let kind = if field.is_some() { MatchKind::Field } else { MatchKind::Phrase };
+
+        phrase_matches(self.system, &mut self.scorer, words, field)
+            .into_iter()
+            .map(|(product_node, word_scores)| {
+                let score = word_scores.iter().map(|(_, bm25)| bm25).sum();
+                let details = word_scores
+                    .into_iter()
+                    .flat_map(|(term_node, bm25)| self.details(&query_text, term_node, product_node, kind, bm25, 1.0))
+                    .collect();
+                (product_node, ProductMatch { matched: 1, score, details })
+            })
+            .collect()
+    }
+
+    /// Detalhe de um Termo alcançado pela consulta (vazio quando `explain` está desligado).
+    fn details(&self, query_term: &str, term_node: NodeIndex, product_node: NodeIndex, kind: MatchKind, bm25: f64, penalty: f64) -> Vec<TermMatch> {
+        if !self.request.explain {
+            return Vec::new();
+        }
+        let graph = &self.system.graph;
+        let term = match graph.node_weight(term_node) {
+            Some(GraphNode::Term(term)) => term.term.clone(),
+            _ => String::new(),
+        };
+        let edges = graph
+            .edges_connecting(term_node, product_node)
+            .map(|edge| *edge.weight())
+            .filter(|edge| edge.kind == EdgeType::CONTAINS_TERM)
+            .collect();
+        vec![TermMatch {
+            query_term: query_term.to_string(),
+            term,
+            term_node: term_node.index(),
+            kind,
+            edges,
+            bm25,
+            penalty,
+            contribution: bm25 * penalty,
+        }]
+    }
+
+    fn add_highlight_terms(&mut self, field: Option<ProductField>, words: &[(String, usize)]) {
+        if self.negation_depth == 0 {
+            self.highlight_terms.extend(words.iter().map(|(term, _)| (field, term.clone())));
+        }
     }
-    
-    // 2. Executar BFS (Busca em Largura) a partir de cada nó de termo
-    for start_node in start_indices {
-        let mut bfs = Bfs::new(graph, start_node);
 
-        while let Some(visited_node) = bfs.next(graph) {
-            // Se o nó visitado for um Produto...
-            if let Some(GraphNode::Product(product)) = graph.node_weight(visited_node) {
-                // Checamos se a aresta é de indexação (Termo -> Produto)
-                let is_index_edge = graph.edges_connecting(start_node, visited_node)
-                    .any(|edge| edge.weight() == &EdgeType::CONTAINS_TERM);
+    /// Interseção (`And`) ou união (`Or`) dos filhos, somando termos atendidos e pontuações.
+    /// Em `And`, filhos negados excluem produtos do grupo (um grupo só de negações parte de
+    /// todos os produtos); em `Or`, um filho negado contribui com o complemento (`tv OR NOT qled`).
+    fn combine(&mut self, children: &[QueryNode], mode: MatchMode) -> Matches {
+        let mut combined: Option<Matches> = None;
+        let mut excluded: Vec<Matches> = Vec::new();
 
-                if is_index_edge {
-                    results_indices.insert(product.clone());
+        for child in children {
+            let mut matches = if let QueryNode::Not(inner) = child {
+                self.negation_depth += 1;
+                let negated = self.evaluate(inner);
+                self.negation_depth -= 1;
+                if mode == MatchMode::And {
+                    excluded.push(negated);
+                    continue;
+                }
+                self.system
+                    .product_id_to_index
+                    .values()
+                    .filter(|product_node| !negated.contains_key(product_node))
+                    .map(|product_node| (*product_node, ProductMatch::default()))
+                    .collect()
+            } else {
+                self.evaluate(child)
+            };
+            combined = Some(match combined {
+                None => matches,
+                Some(mut acc) => {
+                    if mode == MatchMode::And {
+                        acc.retain(|product_node, _| matches.contains_key(product_node));
+                        matches.retain(|product_node, _| acc.contains_key(product_node));
+                    }
+                    for (product_node, product_match) in matches {
+                        acc.entry(product_node).or_default().merge(product_match);
+                    }
+                    acc
                 }
+            });
+        }
+
+        let mut combined = combined.unwrap_or_else(|| {
+            self.system.product_id_to_index.values().map(|product_node| (*product_node, ProductMatch::default())).collect()
+        });
+        combined.retain(|product_node, _| !excluded.iter().any(|matches| matches.contains_key(product_node)));
+        combined
+    }
+}
+
+/// Posições (campo, posição) de cada nó de Termo no produto, lidas das arestas `CONTAINS_TERM`.
+fn term_positions(system: &SearchSystem, product_node: NodeIndex) -> HashMap<NodeIndex, Vec<(ProductField, usize)>> {
+    let mut positions: HashMap<NodeIndex, Vec<(ProductField, usize)>> = HashMap::new();
+    for edge in system.graph.edges_directed(product_node, Direction::Incoming) {
+        if let (EdgeType::CONTAINS_TERM, Some(field), Some(position)) =
+            (edge.weight().kind, edge.weight().field, edge.weight().position)
+        {
+            positions.entry(edge.source()).or_default().push((field, position));
+        }
+    }
+    positions
+}
+
+/// Produtos que contêm a frase (termos exatos, em posições consecutivas de um mesmo campo,
+/// restrito a `field` quando informado), com o BM25 de cada termo da frase.
+fn phrase_matches(
+    system: &SearchSystem,
+    scorer: &mut Bm25Scorer,
+    words: &[(String, usize)],
+    field: Option<ProductField>,
+) -> HashMap<NodeIndex, Vec<(NodeIndex, f64)>> {
+    let Some(nodes) = words.iter().map(|(term, _)| system.term_index.get(term)).collect::<Option<Vec<NodeIndex>>>() else {
+        return HashMap::new();
+    };
+
+    let mut scores: HashMap<NodeIndex, Vec<(NodeIndex, f64)>> = HashMap::new();
+    for (i, &term_node) in nodes.iter().enumerate() {
+        // Termos repetidos na frase ("tv tv") contam uma única vez na pontuação.
+        if nodes[..i].contains(&term_node) {
+            continue;
+        }
+        for (product_node, score) in scorer.score_term(term_node) {
+            scores.entry(product_node).or_default().push((term_node, score));
+        }
+    }
+
+    let first_position = words[0].1;
+    scores.retain(|&product_node, _| {
+        let positions = term_positions(system, product_node);
+        let starts = positions.get(&nodes[0]).into_iter().flatten();
+        starts.filter(|(found, _)| field.is_none_or(|field| field == *found)).any(|&(field, start)| {
+            nodes.iter().zip(words).skip(1).all(|(node, (_, position))| {
+                let expected = (field, start + position - first_position);
+                positions.get(node).is_some_and(|found| found.contains(&expected))
+            })
+        })
+    });
+    scores
+}
+
+/// Multiplicador de proximidade do produto: para cada par de termos vizinhos da consulta
+/// presentes no mesmo campo, `1 + boost / distância` (termos invertidos contam uma posição a mais).
+fn proximity_factor(system: &SearchSystem, product_node: NodeIndex, term_candidates: &[Vec<NodeIndex>], boost: f64) -> f64 {
+    if term_candidates.len() < 2 || boost <= 0.0 {
+        return 1.0;
+    }
+
+    let positions = term_positions(system, product_node);
+    let positions_of = |candidates: &[NodeIndex]| -> Vec<(ProductField, usize)> {
+        candidates.iter().filter_map(|node| positions.get(node)).flatten().copied().collect()
+    };
+
+    let mut factor = 1.0;
+    for pair in term_candidates.windows(2) {
+        let (left, right) = (positions_of(&pair[0]), positions_of(&pair[1]));
+        let distance = left
+            .iter()
+            .flat_map(|&(left_field, left_position)| {
+                right.iter().filter(move |(right_field, _)| *right_field == left_field).map(move |&(_, right_position)| {
+                    if right_position > left_position {
+                        right_position - left_position
+                    } else {
+                        left_position - right_position + 1
+                    }
+                })
+            })
+            .min();
+        if let Some(distance) = distance {
+            factor *= 1.0 + boost / distance as f64;
+        }
+    }
+    factor
+}
+
+/// Ordena os resultados conforme `sort`, sempre terminando no ID do produto como desempate.
+fn sort_hits(system: &SearchSystem, hits: &mut [SearchHit], sort: SortOrder) {
+    let by_relevance = |a: &SearchHit, b: &SearchHit| {
+        b.matched_terms.cmp(&a.matched_terms).then(b.score.total_cmp(&a.score))
+    };
+
+    match sort {
+        SortOrder::Relevance => hits.sort_by(|a, b| by_relevance(a, b).then(a.product.id.cmp(&b.product.id))),
+        SortOrder::Name => {
+            hits.sort_by_cached_key(|hit| (fold_diacritics(&hit.product.name.to_lowercase()), hit.product.id))
+        }
+        SortOrder::Popularity => {
+            let purchases: HashMap<u32, usize> = hits
+                .iter()
+                .map(|hit| {
+                    let count = system.product_id_to_index
+                        .get(&hit.product.id)
+                        .map(|index| system.purchase_count(*index))
+                        .unwrap_or(0);
+                    (hit.product.id, count)
+                })
+                .collect();
+            hits.sort_by(|a, b| {
+                purchases[&b.product.id].cmp(&purchases[&a.product.id])
+                    .then(by_relevance(a, b))
+                    .then(a.product.id.cmp(&b.product.id))
+            });
+        }
+        SortOrder::Newest => hits.sort_by(|a, b| {
+            // `Some` > `None`, então produtos sem data ficam no fim da ordem decrescente.
+            b.product.created_at.cmp(&a.product.created_at).then(a.product.id.cmp(&b.product.id))
+        }),
+    }
+}
+
+/// Filtros já normalizados pelo analisador do sistema.
+struct FilterMatcher<'a> {
+    system: &'a SearchSystem,
+    brand: Option<Vec<String>>,
+    category: Option<Vec<String>>,
+    tag: Option<Vec<String>>,
+}
+
+impl<'a> FilterMatcher<'a> {
+    fn new(system: &'a SearchSystem, filters: &SearchFilters) -> Self {
+        let normalize = |value: &Option<String>| value.as_deref().map(|v| system.analyzer.terms(v));
+        FilterMatcher {
+            system,
+            brand: normalize(&filters.brand),
+            category: normalize(&filters.category),
+            tag: normalize(&filters.tag),
+        }
+    }
+
+    /// Resultado de cada filtro (marca, categoria, tag) para o produto.
+    fn check(&self, product: &Product) -> (bool, bool, bool) {
+        let analyzer = &self.system.analyzer;
+        let brand_ok = self.brand.as_ref().is_none_or(|brand| analyzer.terms(&product.brand) == *brand);
+        let category_ok = self.category.as_ref().is_none_or(|category| analyzer.terms(&product.category) == *category);
+        let tag_ok = self.tag.as_ref().is_none_or(|tag| product.tags.iter().any(|t| analyzer.terms(t) == *tag));
+        (brand_ok, category_ok, tag_ok)
+    }
+
+    fn matches(&self, product: &Product) -> bool {
+        let (brand_ok, category_ok, tag_ok) = self.check(product);
+        brand_ok && category_ok && tag_ok
+    }
+}
+
+/// Nós de Termo que atendem um termo da consulta, com o fator aplicado à pontuação de cada um
+/// e como foram alcançados: 1.0 para correspondência exata, `PARTIAL_MATCH_PENALTY` para
+/// correspondência parcial, `SYNONYM_PENALTY` para sinônimos (multiplicado por
+/// `PARTIAL_MATCH_PENALTY` se o sinônimo só casar parcialmente) e `FUZZY_DISTANCE_PENALTY` por
+/// edição para correspondências aproximadas.
+fn candidate_terms(system: &SearchSystem, request: &SearchRequest, query_term: &str) -> Vec<(NodeIndex, f64, MatchKind)> {
+    let exact = system.term_index.get(query_term);
+    let mut candidates: Vec<(NodeIndex, f64, MatchKind)> = system.term_index
+        .partial_matches(query_term)
+        .into_iter()
+        .map(|node| {
+            if Some(node) == exact {
+                (node, 1.0, MatchKind::Exact)
+            } else {
+                (node, PARTIAL_MATCH_PENALTY, MatchKind::Partial)
+            }
+        })
+        .collect();
+
+    let mut add = |node: NodeIndex, penalty: f64, kind: MatchKind| {
+        match candidates.iter_mut().find(|(existing, _, _)| *existing == node) {
+            Some(best) if penalty > best.1 => *best = (node, penalty, kind),
+            Some(_) => {}
+            None => candidates.push((node, penalty, kind)),
+        }
+    };
+
+    if request.synonyms {
+        for synonym in system.synonyms.expansions(query_term) {
+            let exact_synonym = system.term_index.get(synonym);
+            for node in system.term_index.partial_matches(synonym) {
+                let penalty = if Some(node) == exact_synonym { SYNONYM_PENALTY } else { SYNONYM_PENALTY * PARTIAL_MATCH_PENALTY };
+                add(node, penalty, MatchKind::Synonym);
             }
         }
     }
+    if request.fuzzy {
+        for (node, distance) in fuzzy_matches(&system.term_index, query_term) {
+            add(node, FUZZY_DISTANCE_PENALTY.powi(distance as i32), MatchKind::Fuzzy);
+        }
+    }
+    candidates
+}
+
+/// Calcula a pontuação BM25 (com pesos por campo, no estilo BM25F) a partir das
+/// arestas `CONTAINS_TERM` do grafo.
+struct Bm25Scorer<'a> {
+    system: &'a SearchSystem,
+    request: &'a SearchRequest,
+    product_count: usize,
+    /// Tamanho de cada campo por produto (quantidade de arestas de indexação), calculado sob demanda.
+    field_lengths: HashMap<NodeIndex, HashMap<ProductField, usize>>,
+}
+
+impl<'a> Bm25Scorer<'a> {
+    fn new(system: &'a SearchSystem, request: &'a SearchRequest) -> Self {
+        Bm25Scorer {
+            system,
+            request,
+            product_count: system.product_id_to_index.len(),
+            field_lengths: HashMap::new(),
+        }
+    }
+
+    /// Pontuação de cada produto alcançado pelo nó de Termo.
+    fn score_term(&mut self, term_node: NodeIndex) -> Vec<(NodeIndex, f64)> {
+        let graph = &self.system.graph;
 
-    // 3. Conversão para Vec<Product> e ordenação simples
-    let mut final_results = results_indices.into_iter().collect::<Vec<_>>();
-    final_results.sort_by_key(|p| p.id);
-    
-    println!("Busca finalizada. Encontrados {} resultados.", final_results.len());
-    
-    final_results
-}
\ No newline at end of file
+        // Frequência do termo por produto e por campo (uma aresta por ocorrência)
+        let mut frequencies: HashMap<NodeIndex, HashMap<ProductField, usize>> = HashMap::new();
+        for edge in graph.edges_directed(term_node, Direction::Outgoing) {
+            if edge.weight().kind != EdgeType::CONTAINS_TERM {
+                continue;
+            }
+            if let Some(field) = edge.weight().field {
+                *frequencies.entry(edge.target()).or_default().entry(field).or_insert(0) += 1;
+            }
+        }
+
+        let document_frequency = frequencies.len() as f64;
+        let n = self.product_count.max(frequencies.len()) as f64;
+        let idf = (1.0 + (n - document_frequency + 0.5) / (document_frequency + 0.5)).ln();
+
+        let Bm25Params { k1, b } = self.request.bm25;
+        let mut scores: Vec<(NodeIndex, f64)> = Vec::with_capacity(frequencies.len());
+        for (product_node, per_field) in frequencies {
+            let mut weighted_tf = 0.0;
+            for (field, tf) in per_field {
+                let average = self.system.index_stats.average_field_length(field, self.product_count);
+                let length = self.field_length(product_node, field) as f64;
+                let normalization = if average > 0.0 { 1.0 - b + b * length / average } else { 1.0 };
+                weighted_tf += self.request.field_weights.get(field) * tf as f64 / normalization;
+            }
+            let score = idf * weighted_tf * (k1 + 1.0) / (weighted_tf + k1);
+            scores.push((product_node, score));
+        }
+        scores
+    }
+
+    fn field_length(&mut self, product_node: NodeIndex, field: ProductField) -> usize {
+        let graph = &self.system.graph;
+        let lengths = self.field_lengths.entry(product_node).or_insert_with(|| {
+            let mut lengths = HashMap::new();
+            for edge in graph.edges_directed(product_node, Direction::Incoming) {
+                if let (EdgeType::CONTAINS_TERM, Some(field)) = (edge.weight().kind, edge.weight().field) {
+                    *lengths.entry(field).or_insert(0) += 1;
+                }
+            }
+            lengths
+        });
+        *lengths.get(&field).unwrap_or(&0)
+    }
+}
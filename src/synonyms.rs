@@ -0,0 +1,135 @@
+// src/synonyms.rs
+
+use crate::analyzer::Analyzer;
+use crate::error::MegaStoreError;
+use crate::models::SearchSystem;
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Dicionário de sinônimos usado para expandir os termos da consulta.
+///
+/// Os termos são guardados já normalizados pelo analisador ("televisão" -> "televisao"),
+/// então a expansão compara exatamente com os termos da consulta analisada.
+#[derive(Debug, Clone, Default)]
+pub struct SynonymMap {
+    expansions: HashMap<String, BTreeSet<String>>,
+}
+
+impl SynonymMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Quantidade de termos que possuem alguma expansão.
+    pub fn len(&self) -> usize {
+        self.expansions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.expansions.is_empty()
+    }
+
+    /// Grupo de sinônimos nos dois sentidos: cada termo expande para todos os outros.
+    pub fn add_group(&mut self, terms: &[String]) {
+        for term in terms {
+            self.add_alias(term, terms);
+        }
+    }
+
+    /// Alias de mão única: `term` expande para `targets`, mas não o contrário.
+    pub fn add_alias(&mut self, term: &str, targets: &[String]) {
+        for target in targets.iter().filter(|target| target.as_str() != term) {
+            self.expansions.entry(term.to_string()).or_default().insert(target.clone());
+        }
+    }
+
+    /// Termos adicionais para os quais `term` deve ser expandido, em ordem alfabética.
+    pub fn expansions(&self, term: &str) -> impl Iterator<Item = &str> {
+        self.expansions.get(term).into_iter().flat_map(|targets| targets.iter().map(String::as_str))
+    }
+
+    /// Lê o dicionário de um texto, uma regra por linha:
+    ///
+    /// * `televisão, tv, televisor` — grupo de sinônimos (nos dois sentidos);
+    /// * `som => soundbar, caixa` — alias de mão única (buscar "som" também traz "soundbar");
+    /// * linhas vazias e iniciadas por `#` são ignoradas.
+    ///
+    /// Cada entrada precisa resultar em exatamente um termo no analisador; o erro
+    /// (`MegaStoreError::InvalidData`) informa a linha da regra inválida.
+    pub fn parse(text: &str, analyzer: &dyn Analyzer) -> Result<Self, MegaStoreError> {
+        Self::from_reader(text.as_bytes(), analyzer)
+    }
+
+    /// Lê o dicionário de qualquer fonte (`BufRead`), no formato descrito em `parse`.
+    pub fn from_reader<R: BufRead>(reader: R, analyzer: &dyn Analyzer) -> Result<Self, MegaStoreError> {
+        let mut map = SynonymMap::new();
+
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line_number = number + 1;
+            let rule = line.trim();
+            if rule.is_empty() || rule.starts_with('#') {
+                continue;
+            }
+
+            match rule.split_once("=>") {
+                Some((sources, targets)) => {
+                    let sources = parse_entries(sources, analyzer, line_number)?;
+                    let targets = parse_entries(targets, analyzer, line_number)?;
+                    for source in &sources {
+                        map.add_alias(source, &targets);
+                    }
+                }
+                None => {
+                    let group = parse_entries(rule, analyzer, line_number)?;
+                    if group.len() < 2 {
+                        return Err(MegaStoreError::InvalidData(format!(
+                            "linha {}: grupo de sinônimos precisa de pelo menos dois termos",
+                            line_number
+                        )));
+                    }
+                    map.add_group(&group);
+                }
+            }
+        }
+
+        Ok(map)
+    }
+
+    /// Lê o dicionário de um arquivo, no formato descrito em `parse`.
+    pub fn from_path<P: AsRef<Path>>(path: P, analyzer: &dyn Analyzer) -> Result<Self, MegaStoreError> {
+        let file = File::open(path)?;
+        Self::from_reader(BufReader::new(file), analyzer)
+    }
+}
+
+impl SearchSystem {
+    /// Carrega um arquivo de sinônimos (normalizado pelo analisador do sistema) e o
+    /// usa nas próximas buscas. Retorna a quantidade de termos com expansão.
+    pub fn load_synonyms<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, MegaStoreError> {
+        self.synonyms = SynonymMap::from_path(path, self.analyzer.as_ref())?;
+        log::info!(terms = self.synonyms.len(); "Dicionário de sinônimos carregado");
+        Ok(self.synonyms.len())
+    }
+}
+
+// --- Funções Auxiliares (Privadas) ---
+
+/// Entradas separadas por vírgula, cada uma normalizada em um único termo.
+fn parse_entries(text: &str, analyzer: &dyn Analyzer, line_number: usize) -> Result<Vec<String>, MegaStoreError> {
+    let mut terms = Vec::new();
+    for entry in text.split(',') {
+        let mut analyzed = analyzer.terms(entry);
+        if analyzed.len() != 1 {
+            return Err(MegaStoreError::InvalidData(format!(
+                "linha {}: a entrada '{}' deve conter exatamente um termo",
+                line_number,
+                entry.trim()
+            )));
+        }
+        terms.push(analyzed.remove(0));
+    }
+    Ok(terms)
+}
@@ -0,0 +1,194 @@
+// src/term_index.rs
+
+use petgraph::graph::NodeIndex;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Tamanho mínimo (em caracteres) do trecho em comum numa correspondência parcial.
+pub const MIN_PARTIAL_FRAGMENT_CHARS: usize = 3;
+
+/// Fração mínima do termo da consulta que um termo contido nela precisa cobrir.
+pub const MIN_CONTAINED_FRAGMENT_RATIO: f64 = 0.6;
+
+/// Índice invertido dos nós de Termo do grafo.
+///
+/// Mantém quatro estruturas sincronizadas:
+/// * Tabela Hash `termo -> NodeIndex` para acesso exato em O(1);
+/// * Dicionário ordenado de termos (B-Tree) para consultas por prefixo em O(log n);
+/// * Índice de sufixos (B-Tree) para encontrar os termos que *contêm* um fragmento
+///   sem percorrer todos os nós do grafo;
+/// * Termos agrupados pelo tamanho (em caracteres), para a busca aproximada só comparar
+///   termos de tamanho próximo ao da consulta.
+///
+/// Guarda também as grafias originais de cada termo ("jeans", "calças"), para exibir ao usuário
+/// uma palavra do catálogo em vez do termo normalizado ("jean", "calca").
+#[derive(Debug, Clone, Default)]
+pub struct TermIndex {
+    by_term: HashMap<String, NodeIndex>,
+    dictionary: BTreeMap<String, NodeIndex>,
+    suffixes: BTreeMap<String, BTreeSet<String>>,
+    by_length: BTreeMap<usize, BTreeSet<String>>,
+    spellings: HashMap<String, BTreeMap<String, usize>>,
+}
+
+impl TermIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Quantidade de termos indexados.
+    pub fn len(&self) -> usize {
+        self.by_term.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_term.is_empty()
+    }
+
+    /// Busca exata de um termo. O(1).
+    pub fn get(&self, term: &str) -> Option<NodeIndex> {
+        self.by_term.get(term).copied()
+    }
+
+    pub fn contains(&self, term: &str) -> bool {
+        self.by_term.contains_key(term)
+    }
+
+    /// Registra (ou substitui) o nó associado a um termo.
+    pub fn insert(&mut self, term: String, index: NodeIndex) {
+        if self.by_term.insert(term.clone(), index).is_none() {
+            for suffix in suffixes_of(&term) {
+                self.suffixes.entry(suffix.to_string()).or_default().insert(term.clone());
+            }
+            self.by_length.entry(term.chars().count()).or_default().insert(term.clone());
+        }
+        self.dictionary.insert(term, index);
+    }
+
+    /// Remove um termo do índice, retornando o nó que estava associado a ele.
+    pub fn remove(&mut self, term: &str) -> Option<NodeIndex> {
+        let index = self.by_term.remove(term)?;
+        self.dictionary.remove(term);
+        self.spellings.remove(term);
+        for suffix in suffixes_of(term) {
+            if let Some(terms) = self.suffixes.get_mut(suffix) {
+                terms.remove(term);
+                if terms.is_empty() {
+                    self.suffixes.remove(suffix);
+                }
+            }
+        }
+        let length = term.chars().count();
+        if let Some(terms) = self.by_length.get_mut(&length) {
+            terms.remove(term);
+            if terms.is_empty() {
+                self.by_length.remove(&length);
+            }
+        }
+        Some(index)
+    }
+
+    /// Registra uma ocorrência de `spelling` (a palavra como aparece no catálogo) para o termo.
+    pub fn add_spelling(&mut self, term: &str, spelling: &str) {
+        *self.spellings.entry(term.to_string()).or_default().entry(spelling.to_string()).or_insert(0) += 1;
+    }
+
+    /// Desfaz uma ocorrência registrada por `add_spelling`.
+    pub fn remove_spelling(&mut self, term: &str, spelling: &str) {
+        if let Some(spellings) = self.spellings.get_mut(term) {
+            if let Some(count) = spellings.get_mut(spelling) {
+                *count -= 1;
+                if *count == 0 {
+                    spellings.remove(spelling);
+                }
+            }
+            if spellings.is_empty() {
+                self.spellings.remove(term);
+            }
+        }
+    }
+
+    /// Forma de exibição do termo: a grafia mais frequente no catálogo (a primeira em ordem
+    /// alfabética em caso de empate), ou o próprio termo se nenhuma foi registrada.
+    pub fn display_form<'a>(&'a self, term: &'a str) -> &'a str {
+        self.spellings
+            .get(term)
+            .and_then(|spellings| {
+                spellings
+                    .iter()
+                    .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+                    .map(|(spelling, _)| spelling.as_str())
+            })
+            .unwrap_or(term)
+    }
+
+    /// Todos os termos em ordem alfabética.
+    pub fn terms(&self) -> impl Iterator<Item = (&str, NodeIndex)> {
+        self.dictionary.iter().map(|(term, index)| (term.as_str(), *index))
+    }
+
+    /// Termos que começam com `prefix`, em ordem alfabética. O(log n + k).
+    pub fn with_prefix<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = (&'a str, NodeIndex)> + 'a {
+        self.dictionary
+            .range::<str, _>((std::ops::Bound::Included(prefix), std::ops::Bound::Unbounded))
+            .take_while(move |(term, _)| term.starts_with(prefix))
+            .map(|(term, index)| (term.as_str(), *index))
+    }
+
+    /// Termos com tamanho (em caracteres) entre `min` e `max`, inclusive, agrupados por tamanho
+    /// e em ordem alfabética dentro de cada tamanho. O(log n + k).
+    pub fn with_length_between(&self, min: usize, max: usize) -> impl Iterator<Item = (&str, NodeIndex)> + '_ {
+        self.by_length
+            .range(min..=max)
+            .flat_map(|(_, terms)| terms.iter())
+            .filter_map(|term| self.by_term.get_key_value(term.as_str()).map(|(term, index)| (term.as_str(), *index)))
+    }
+
+    /// Termos que contêm `fragment` em qualquer posição (via índice de sufixos).
+    pub fn containing(&self, fragment: &str) -> BTreeSet<&str> {
+        self.suffixes
+            .range::<str, _>((std::ops::Bound::Included(fragment), std::ops::Bound::Unbounded))
+            .take_while(|(suffix, _)| suffix.starts_with(fragment))
+            .flat_map(|(_, terms)| terms.iter().map(String::as_str))
+            .collect()
+    }
+
+    /// Termos que aparecem como trecho de `text` (ex.: "tv" em "smarttv").
+    pub fn contained_in(&self, text: &str) -> BTreeSet<&str> {
+        let boundaries: Vec<usize> = text.char_indices().map(|(i, _)| i).chain([text.len()]).collect();
+        let mut found = BTreeSet::new();
+
+        for (i, &start) in boundaries.iter().enumerate() {
+            for &end in &boundaries[i + 1..] {
+                if let Some((term, _)) = self.by_term.get_key_value(&text[start..end]) {
+                    found.insert(term.as_str());
+                }
+            }
+        }
+        found
+    }
+
+    /// Correspondência parcial usada pela busca: o próprio termo, termos contidos na consulta ou
+    /// que contêm a consulta. Retorna os nós em ordem alfabética do termo.
+    ///
+    /// O trecho em comum precisa ter ao menos `MIN_PARTIAL_FRAGMENT_CHARS` caracteres e, quando
+    /// o termo está contido na consulta, cobrir ao menos `MIN_CONTAINED_FRAGMENT_RATIO` dela:
+    /// assim "b" não alcança todo termo com a letra b, nem "levis" alcança "televisao".
+    pub fn partial_matches(&self, query: &str) -> Vec<NodeIndex> {
+        let query_chars = query.chars().count();
+        let mut terms: BTreeSet<&str> = self.by_term.get_key_value(query).map(|(term, _)| term.as_str()).into_iter().collect();
+        if query_chars >= MIN_PARTIAL_FRAGMENT_CHARS {
+            terms.extend(self.containing(query));
+        }
+        terms.extend(self.contained_in(query).into_iter().filter(|term| {
+            let term_chars = term.chars().count();
+            term_chars >= MIN_PARTIAL_FRAGMENT_CHARS
+                && term_chars as f64 >= MIN_CONTAINED_FRAGMENT_RATIO * query_chars as f64
+        }));
+        terms.into_iter().filter_map(|term| self.get(term)).collect()
+    }
+}
+
+/// Sufixos de um termo, respeitando os limites de caracteres UTF-8.
+fn suffixes_of(term: &str) -> impl Iterator<Item = &str> {
+    term.char_indices().map(move |(i, _)| &term[i..])
+}
@@ -2,7 +2,21 @@
 
 // Importa as funções e estruturas necessárias usando o nome da crate.
 // Se o nome no seu Cargo.toml for diferente, ajuste a primeira parte.
-use megastore_busca_otimizada::{graph_builder, search, recommender}; 
+use megastore_busca_otimizada::{graph_builder, search, recommender, ingest, csv_import, MegaStoreError};
+use megastore_busca_otimizada::models::{Product, User, Interaction, InteractionType, GraphEdge, GraphNode, EdgeType};
+use petgraph::visit::EdgeRef;
+use megastore_busca_otimizada::term_index::TermIndex;
+use megastore_busca_otimizada::search::{MatchMode, SearchRequest, FieldWeights, SortOrder};
+use megastore_busca_otimizada::models::Catalog;
+use megastore_busca_otimizada::analyzer::{Analyzer, PortugueseAnalyzer};
+use megastore_busca_otimizada::fuzzy;
+use megastore_busca_otimizada::highlight::{self, HighlightMarkers};
+use megastore_busca_otimizada::explain::MatchKind;
+use megastore_busca_otimizada::recommender::{InteractionWeights, NeighborSimilarity, RecommendOptions};
+use megastore_busca_otimizada::item_similarity::{self, ItemSimilarityWeights};
+use megastore_busca_otimizada::synonyms::SynonymMap;
+use std::collections::BTreeSet;
+use std::time::Duration;
 
 // Define uma função de teste básica para inicialização do sistema
 #[test]
@@ -64,4 +78,880 @@ fn test_recommendation_collaborative_filtering() {
     // Cenário: Usuário inexistente
     let recs_invalid = recommender::recommend_for_user(&system, 999);
     assert_eq!(recs_invalid.len(), 0, "Usuário inexistente não deve gerar recomendações.");
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_build_system_from_json_catalog() {
+    // O catálogo JSON de exemplo contém os mesmos dados do mock.
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/catalog.json");
+    let system = graph_builder::build_system_from_path(path).expect("O catálogo JSON deve ser válido.");
+    let mock = graph_builder::build_system_from_data();
+
+    assert_eq!(system.product_id_to_index.len(), 5);
+    assert_eq!(system.user_id_to_index.len(), 3);
+    assert_eq!(system.graph.node_count(), mock.graph.node_count(), "O grafo deve ser equivalente ao construído com o mock.");
+    assert_eq!(system.graph.edge_count(), mock.graph.edge_count(), "O grafo deve ser equivalente ao construído com o mock.");
+
+    let recs_alice = recommender::recommend_for_user(&system, 1);
+    assert_eq!(recs_alice.len(), 1);
+    assert_eq!(recs_alice[0].id, 104);
+}
+
+#[test]
+fn test_build_system_from_reader_rejects_dangling_interaction() {
+    let json = r#"{
+        "products": [{ "id": 1, "name": "Mouse", "brand": "X", "category": "Informática", "tags": [] }],
+        "users": [{ "id": 1, "name": "Alice" }],
+        "interactions": [{ "user_id": 1, "product_id": 999, "type": "BOUGHT" }]
+    }"#;
+
+    let result = graph_builder::build_system_from_reader(json.as_bytes());
+    assert!(matches!(
+        result,
+        Err(MegaStoreError::DanglingInteraction { user_id: 1, product_id: 999 })
+    ), "Interação com produto inexistente deve gerar erro, não panic.");
+
+    // Dados inválidos numa interação conhecida não são confundidos com IDs inexistentes.
+    let bad_rating = json.replace(r#""product_id": 999, "type": "BOUGHT""#, r#""product_id": 1, "type": "RATED", "rating": 9"#);
+    assert!(matches!(
+        graph_builder::build_system_from_reader(bad_rating.as_bytes()),
+        Err(MegaStoreError::InvalidData(_))
+    ));
+
+    let invalid = graph_builder::build_system_from_reader("{ nao e json".as_bytes());
+    assert!(matches!(invalid, Err(MegaStoreError::Parse(_))));
+}
+
+#[test]
+fn test_ingest_interactions_jsonl() {
+    let mut system = graph_builder::build_system_from_data();
+    let edges_before = system.graph.edge_count();
+
+    // Charlie (3) passa a interagir com a Soundbar (102): vira vizinho de Alice e Bob.
+    let log = "\
+{\"user_id\": 3, \"product_id\": 102, \"event\": \"BOUGHT\"}
+
+{\"user_id\": 3, \"product_id\": 101, \"event\": \"VIEWED\"}
+linha quebrada
+{\"user_id\": 42, \"product_id\": 101, \"event\": \"BOUGHT\"}
+{\"user_id\": 1, \"product_id\": 999, \"event\": \"VIEWED\"}
+";
+    let report = ingest::ingest_interactions_jsonl(&mut system, log.as_bytes()).expect("Sem erros de E/S.");
+
+    assert_eq!(report.lines_read, 5, "Linhas vazias não devem ser contabilizadas.");
+    assert_eq!(report.interactions_added, 2);
+    assert_eq!(report.lines_failed, 3);
+    assert_eq!(system.graph.edge_count(), edges_before + 2);
+
+    assert_eq!(report.errors[0].line, 4);
+    assert!(matches!(report.errors[0].error, MegaStoreError::Parse(_)));
+    assert!(matches!(report.errors[1].error, MegaStoreError::UnknownUser(42)));
+    assert!(matches!(report.errors[2].error, MegaStoreError::UnknownProduct(999)));
+
+    // Uma linha com bytes fora do UTF-8 é descartada sem perder as seguintes.
+    let mut log = b"{\"user_id\": 2, \"product_id\": 103, \"event\": \"VIEWED\"}\n".to_vec();
+    log.extend_from_slice(b"{\"user_id\": 2, \"product_id\": \xff}\n");
+    log.extend_from_slice(b"{\"user_id\": 2, \"product_id\": 105, \"event\": \"VIEWED\"}\n");
+    let report = ingest::ingest_interactions_jsonl(&mut system, log.as_slice()).expect("Sem erros de E/S.");
+    assert_eq!((report.lines_read, report.interactions_added, report.lines_failed), (3, 2, 1));
+    assert_eq!(report.errors[0].line, 2);
+    assert!(matches!(report.errors[0].error, MegaStoreError::InvalidData(_)));
+
+    // As novas arestas participam da recomendação.
+    let recs_charlie = recommender::recommend_for_user(&system, 3);
+    assert!(recs_charlie.iter().any(|p| p.id == 104), "Charlie agora compartilha a Soundbar com Bob.");
+}
+
+#[test]
+fn test_csv_import_matches_mock_system() {
+    // Exportação com colunas em português e delimitador ';' (tags separadas por '|').
+    let products = "\
+codigo;nome;marca;categoria;etiquetas
+101;Smart TV 4K LG 55;LG;Eletrônicos;tv|smart
+102;Soundbar JBL 5.1;JBL;Eletrônicos;audio|speaker
+103;Vestido Floral Verão;Zara;Vestuário;roupa|verao
+104;Smart TV QLED Samsung 65;Samsung;Eletrônicos;tv|qled
+105;Calça Jeans Slim Fit;Levi's;Vestuário;jeans|calca
+abc;Produto Sem ID;X;Y;
+101;TV Repetida;LG;Eletrônicos;tv
+";
+    let users = "codigo;nome\n1;Alice\n2;Bob\n3;Charlie\n2;Bob Repetido\n";
+    let interactions = "\
+cliente;produto;evento
+1;101;BOUGHT
+1;102;VIEWED
+2;104;BOUGHT
+2;102;BOUGHT
+3;103;BOUGHT
+3;105;VIEWED
+3;999;VIEWED
+";
+
+    let mut mapping = csv_import::CsvMapping { delimiter: b';', ..Default::default() };
+    mapping.products = csv_import::ProductColumns {
+        id: "codigo".into(), name: "nome".into(), brand: "marca".into(),
+        category: "categoria".into(), tags: "etiquetas".into(), created_at: "cadastro".into(), tag_separator: '|',
+    };
+    mapping.users = csv_import::UserColumns { id: "codigo".into(), name: "nome".into() };
+    mapping.interactions = csv_import::InteractionColumns {
+        user_id: "cliente".into(), product_id: "produto".into(), event: "evento".into(), rating: "nota".into(),
+        timestamp: "data".into(), quantity: "quantidade".into(),
+    };
+
+    let import = csv_import::import_catalog_csv(products.as_bytes(), users.as_bytes(), Some(interactions.as_bytes()), &mapping)
+        .expect("Os cabeçalhos mapeados existem.");
+    let mock = graph_builder::build_system_from_data();
+
+    assert_eq!(import.system.graph.node_count(), mock.graph.node_count());
+    assert_eq!(import.system.graph.edge_count(), mock.graph.edge_count());
+    assert_eq!(search::search_by_term(&import.system, "verao").len(), 1);
+
+    // Linha 7 de produtos (ID inválido), IDs repetidos (a primeira ocorrência é mantida)
+    // e linha 8 de interações (produto inexistente).
+    let lines: Vec<(&str, u64)> = import.errors.iter().map(|e| (e.source, e.line)).collect();
+    assert_eq!(lines, vec![("products", 7), ("products", 8), ("users", 5), ("interactions", 8)]);
+    assert!(matches!(import.errors[0].error, MegaStoreError::InvalidData(_)));
+    assert!(matches!(&import.errors[1].error, MegaStoreError::InvalidData(message) if message.contains("101")));
+    assert!(matches!(import.errors[2].error, MegaStoreError::InvalidData(_)));
+    assert!(matches!(import.errors[3].error, MegaStoreError::UnknownProduct(999)));
+
+    // As tags mantêm a grafia original, como no catálogo JSON.
+    let cased = csv_import::read_products_csv("codigo;nome;marca;categoria;etiquetas\n7;TV;LG;Eletrônicos;Smart| TV \n".as_bytes(), &mapping.products, b';').unwrap();
+    assert_eq!(cased.records[0].tags, vec!["Smart", "TV"]);
+
+    // Cabeçalho sem a coluna mapeada é um erro imediato.
+    let missing = csv_import::read_users_csv("id;nome\n1;Alice\n".as_bytes(), &csv_import::UserColumns::default(), b';');
+    assert!(matches!(missing, Err(MegaStoreError::InvalidData(_))));
+}
+
+#[test]
+fn test_result_variants_report_typed_errors() {
+    let system = graph_builder::build_system_from_data();
+
+    let recs = recommender::try_recommend_for_user(&system, 1).expect("Alice existe.");
+    assert_eq!(recs.len(), 1);
+    assert!(matches!(recommender::try_recommend_for_user(&system, 999), Err(MegaStoreError::UnknownUser(999))));
+
+    assert_eq!(search::try_search_by_term(&system, "tv").expect("Consulta válida.").len(), 2);
+    assert!(search::try_search_by_term(&system, "banana").expect("Consulta válida.").is_empty());
+    assert!(matches!(search::try_search_by_term(&system, "   "), Err(MegaStoreError::InvalidData(_))));
+
+    // IDs duplicados no catálogo são rejeitados.
+    let json = r#"{"users": [{ "id": 1, "name": "Alice" }, { "id": 1, "name": "Outra Alice" }]}"#;
+    assert!(matches!(graph_builder::build_system_from_reader(json.as_bytes()), Err(MegaStoreError::InvalidData(_))));
+}
+
+#[test]
+fn test_incremental_updates_keep_indexes_valid() {
+    let mut system = graph_builder::build_system_from_data();
+
+    // Novo produto e novo cliente sem reconstruir o grafo.
+    system.upsert_product(Product {
+        id: 106, name: "Fone Bluetooth JBL".into(), brand: "JBL".into(),
+        category: "Eletrônicos".into(), tags: vec!["audio".into(), "fone".into()], created_at: None,
+    });
+    system.upsert_user(User { id: 4, name: "Diana".into() });
+    system.record_interaction(&Interaction { user_id: 4, product_id: 106, interaction_type: InteractionType::Bought, rating: None, timestamp: None, quantity: None }).unwrap();
+    system.record_interaction(&Interaction { user_id: 4, product_id: 102, interaction_type: InteractionType::Bought, rating: None, timestamp: None, quantity: None }).unwrap();
+    assert!(matches!(
+        system.record_interaction(&Interaction { user_id: 9, product_id: 106, interaction_type: InteractionType::Viewed, rating: None, timestamp: None, quantity: None }),
+        Err(MegaStoreError::UnknownUser(9))
+    ));
+
+    assert_eq!(search::search_by_term(&system, "fone").len(), 1);
+    assert_eq!(search::search_by_term(&system, "audio").len(), 2);
+    assert!(recommender::recommend_for_user(&system, 1).iter().any(|p| p.id == 106), "Diana compartilha a Soundbar com Alice.");
+
+    // Atualização: o produto muda de nome e tags; os termos antigos deixam de apontar para ele.
+    system.upsert_product(Product {
+        id: 106, name: "Headphone Bluetooth JBL".into(), brand: "JBL".into(),
+        category: "Eletrônicos".into(), tags: vec!["audio".into()], created_at: None,
+    });
+    assert!(search::search_by_term(&system, "fone").is_empty());
+    assert!(!system.term_index.contains("fone"), "Termos órfãos devem ser removidos.");
+    assert_eq!(search::search_by_term(&system, "headphone").len(), 1);
+    assert!(recommender::recommend_for_user(&system, 1).iter().any(|p| p.id == 106), "As interações são preservadas.");
+
+    // Remoção: os índices restantes continuam válidos (StableGraph).
+    let removed = system.remove_product(101).expect("Produto existe.");
+    assert_eq!(removed.id, 101);
+    assert!(matches!(system.remove_product(101), Err(MegaStoreError::UnknownProduct(101))));
+    let results_tv = search::search_by_term(&system, "tv");
+    assert_eq!(results_tv.len(), 1);
+    assert_eq!(results_tv[0].id, 104);
+
+    system.remove_user(2).expect("Bob existe.");
+    assert_eq!(system.user_id_to_index.len(), 3);
+    let recs_alice = recommender::recommend_for_user(&system, 1);
+    assert!(!recs_alice.iter().any(|p| p.id == 104), "Sem Bob, a TV Samsung não é mais recomendada.");
+    assert!(recs_alice.iter().any(|p| p.id == 106));
+}
+
+#[test]
+fn test_term_index_lookups() {
+    let system = graph_builder::build_system_from_data();
+    let index = &system.term_index;
+
+    // Busca exata O(1) e dicionário ordenado por prefixo.
+    assert!(index.get("samsung").is_some());
+    let prefixed: Vec<&str> = index.with_prefix("s").map(|(term, _)| term).collect();
+    assert_eq!(prefixed, vec!["samsung", "slim", "smart", "soundbar", "speaker"]);
+
+    // Correspondência parcial nos dois sentidos, sem varrer o grafo.
+    assert!(index.containing("sung").contains("samsung"));
+    assert!(index.contained_in("smarttv").contains("tv"));
+    assert_eq!(index.partial_matches("qle").len(), 1);
+
+    // Grafia original de cada termo, para exibição.
+    assert_eq!(index.display_form("jean"), "jeans");
+    assert_eq!(index.display_form("inexistente"), "inexistente");
+
+    // Trechos curtos demais não bastam: "levis" em "televisao" e letras soltas.
+    assert!(index.partial_matches("televisao").is_empty());
+    assert!(search::search_by_term(&system, "televisão").iter().all(|p| p.id != 105));
+    assert!(search::search_by_term(&system, "a:b:c").is_empty());
+
+    // Inserção e remoção mantêm as estruturas sincronizadas.
+    let mut custom = TermIndex::new();
+    let node = system.term_index.get("tv").unwrap();
+    custom.insert("televisão".to_string(), node);
+    assert!(custom.containing("visã").contains("televisão"));
+    assert_eq!(custom.remove("televisão"), Some(node));
+    assert!(custom.is_empty());
+    assert!(custom.containing("visã").is_empty());
+    assert_eq!(custom.with_length_between(0, usize::MAX).count(), 0);
+
+    // Termos por tamanho: a busca aproximada só compara termos de tamanho próximo.
+    let sized: Vec<&str> = index.with_length_between(7, 7).map(|(term, _)| term).collect();
+    assert!(sized.contains(&"samsung") && sized.iter().all(|term| term.chars().count() == 7));
+}
+
+#[test]
+fn test_multi_word_query_modes() {
+    let system = graph_builder::build_system_from_data();
+
+    // OR: qualquer termo; ordenado pela quantidade de termos atendidos.
+    let or = search::search(&system, &SearchRequest::new("smart tv samsung")).unwrap();
+    let ids: Vec<u32> = or.hits.iter().map(|hit| hit.product.id).collect();
+    assert_eq!(ids, vec![104, 101], "A TV Samsung atende 3 termos, a TV LG atende 2.");
+    assert_eq!(or.hits[0].matched_terms, 3);
+    assert_eq!(or.hits[1].matched_terms, 2);
+
+    // AND: todos os termos precisam corresponder.
+    let and = search::search(&system, &SearchRequest::new("Smart TV Samsung").with_mode(MatchMode::And)).unwrap();
+    assert_eq!(and.hits.len(), 1);
+    assert_eq!(and.hits[0].product.id, 104);
+
+    let none = search::search(&system, &SearchRequest::new("tv vestido").with_mode(MatchMode::And)).unwrap();
+    assert!(none.hits.is_empty());
+    let either = search::search(&system, &SearchRequest::new("tv vestido")).unwrap();
+    assert_eq!(either.hits.len(), 3);
+}
+
+#[test]
+fn test_bm25_scoring_with_field_weights() {
+    let product = |id: u32, name: &str, tags: &[&str]| Product {
+        id, name: name.into(), brand: "Genérica".into(), category: "Acessórios".into(),
+        tags: tags.iter().map(|t| t.to_string()).collect(), created_at: None,
+    };
+    let system = graph_builder::build_system(Catalog {
+        products: vec![
+            product(1, "Cabo HDMI", &["tv"]),
+            product(2, "Suporte TV Articulado", &[]),
+            product(3, "Controle Remoto", &["universal"]),
+        ],
+        ..Default::default()
+    }).unwrap();
+
+    // Pesos padrão: o nome pesa mais que as tags.
+    let scored = search::search_scored(&system, "tv").unwrap();
+    assert_eq!(scored.len(), 2);
+    assert_eq!(scored[0].0.id, 2, "Match no nome deve ficar à frente de match na tag.");
+    assert!(scored[0].1 > scored[1].1 && scored[1].1 > 0.0);
+
+    // Dando mais peso às tags, a ordem se inverte.
+    let weights = FieldWeights { name: 1.0, tags: 5.0, ..Default::default() };
+    let boosted = search::search(&system, &SearchRequest::new("tv").with_field_weights(weights)).unwrap();
+    assert_eq!(boosted.hits[0].product.id, 1);
+
+    // IDF: termos raros valem mais que termos comuns (categoria presente em todos os produtos).
+    let rare = search::search_scored(&system, "hdmi").unwrap();
+    let common = search::search_scored(&system, "acessórios").unwrap();
+    assert_eq!(common.len(), 3, "Marca e categoria também são indexadas.");
+    assert!(rare[0].1 > common[0].1);
+}
+
+#[test]
+fn test_portuguese_analyzer_normalizes_index_and_query() {
+    let analyzer = PortugueseAnalyzer::default();
+
+    // Acentos, pontuação, stopwords e plurais.
+    assert_eq!(analyzer.terms("Calças da Levi's 5.1"), vec!["calca", "levis", "51"]);
+    assert_eq!(analyzer.terms("Televisões, Papéis e Lápis!"), vec!["televisao", "papel", "lapis"]);
+
+    // Posições contam as stopwords removidas; offsets apontam para o texto original.
+    let tokens = analyzer.analyze("Vestido de Verão");
+    assert_eq!(tokens.len(), 2);
+    assert_eq!((tokens[1].term.as_str(), tokens[1].position), ("verao", 2));
+    assert_eq!(&"Vestido de Verão"[tokens[1].start..tokens[1].end], "Verão");
+
+    // A mesma análise vale para indexação e consulta.
+    let system = graph_builder::build_system_from_data();
+    let with_accent = search::search_by_term(&system, "verão");
+    let without_accent = search::search_by_term(&system, "VERAO");
+    assert_eq!(with_accent.len(), 1);
+    assert_eq!(with_accent[0].id, without_accent[0].id);
+
+    let pants = search::search_by_term(&system, "calças");
+    assert_eq!(pants.len(), 1);
+    assert_eq!(pants[0].id, 105);
+    assert_eq!(search::search_by_term(&system, "levis")[0].id, 105);
+    assert!(matches!(search::try_search_by_term(&system, "de para"), Err(MegaStoreError::InvalidData(_))), "Só stopwords equivale a consulta vazia.");
+}
+
+#[test]
+fn test_fuzzy_search_tolerates_typos() {
+    assert_eq!(fuzzy::damerau_levenshtein("samsumg", "samsung"), 1, "Transposição conta como uma edição.");
+    assert_eq!(fuzzy::damerau_levenshtein("jean", "jen"), 1);
+    assert_eq!(fuzzy::damerau_levenshtein("", "tv"), 2);
+    assert_eq!(fuzzy::max_edits("tv"), 0);
+
+    let system = graph_builder::build_system_from_data();
+
+    // Sem o modo aproximado, erros de digitação não encontram nada.
+    assert!(search::search(&system, &SearchRequest::new("samsumg")).unwrap().hits.is_empty());
+
+    let typo = search::search(&system, &SearchRequest::new("samsumg").with_fuzzy(true)).unwrap();
+    assert_eq!(typo.hits.len(), 1);
+    assert_eq!(typo.hits[0].product.id, 104);
+
+    let jeans = search::search(&system, &SearchRequest::new("jens").with_fuzzy(true)).unwrap();
+    assert!(jeans.hits.iter().any(|hit| hit.product.id == 105));
+
+    // O casamento aproximado pontua menos que o exato.
+    let exact = search::search(&system, &SearchRequest::new("samsung").with_fuzzy(true)).unwrap();
+    assert!(exact.hits[0].score > typo.hits[0].score);
+}
+
+#[test]
+fn test_autocomplete_ranks_by_popularity() {
+    let system = graph_builder::build_system_from_data();
+
+    let suggestions = system.autocomplete("s", 3);
+    let terms: Vec<&str> = suggestions.terms.iter().map(|s| s.term.as_str()).collect();
+    // smart: nome e tag da TV LG + nome da TV Samsung, ambas compradas (uma compra cada).
+    assert_eq!(terms, vec!["smart", "samsung", "soundbar"]);
+    assert_eq!(suggestions.terms[0].popularity, 5);
+    assert_eq!(suggestions.terms[1].popularity, 3);
+    let products: Vec<u32> = suggestions.products.iter().map(|s| s.product_id).collect();
+    assert_eq!(products, vec![104, 101, 102], "A TV Samsung tem duas palavras com 's' e uma compra.");
+
+    // Palavras já completas restringem os produtos; a última é o prefixo.
+    let smart_t = system.autocomplete("Smart T", 5);
+    assert_eq!(smart_t.terms[0].term, "tv");
+    let names: Vec<&str> = smart_t.products.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["Smart TV 4K LG 55", "Smart TV QLED Samsung 65"]);
+
+    // Prefixos que são stopwords ou terminam em "s" não passam pelo analisador.
+    let mut system = system;
+    system.upsert_product(Product { id: 106, name: "Notebook Dell".to_string(), brand: "Dell".to_string(), category: "Informática".to_string(), tags: vec![], created_at: None });
+    assert_eq!(system.autocomplete("no", 5).terms[0].term, "notebook");
+    assert_eq!(system.autocomplete("Calça JEANS", 5).terms[0].term, "jeans");
+
+    // A grafia exibida acompanha o catálogo quando produtos entram e saem.
+    system.upsert_product(Product { id: 107, name: "Jean Jean Jean".to_string(), brand: "Genérica".to_string(), category: "Moda".to_string(), tags: vec![], created_at: None });
+    assert_eq!(system.autocomplete("jea", 5).terms[0].term, "jean");
+    system.remove_product(107).unwrap();
+    assert_eq!(system.autocomplete("jea", 5).terms[0].term, "jeans");
+
+    assert_eq!(system.autocomplete("   ", 5), Default::default());
+    assert!(system.autocomplete("xyz", 5).terms.is_empty());
+}
+
+#[test]
+fn test_did_you_mean_suggestions_for_zero_results() {
+    let system = graph_builder::build_system_from_data();
+
+    let response = search::search(&system, &SearchRequest::new("samsumg")).unwrap();
+    assert!(response.hits.is_empty());
+    assert_eq!(response.suggestions, vec!["samsung"]);
+
+    // Termos conhecidos são mantidos; apenas o desconhecido é corrigido.
+    let response = search::search(&system, &SearchRequest::new("smart qlde").with_mode(MatchMode::And)).unwrap();
+    assert!(response.hits.is_empty());
+    assert_eq!(response.suggestions[0], "smart qled");
+
+    // Só a palavra errada é trocada: negações, campos e palavras sem correção ficam como estão.
+    let suggest = |query: &str| search::search(&system, &SearchRequest::new(query)).unwrap().suggestions;
+    assert_eq!(suggest("samsumg -tv")[0], "samsung -tv");
+    assert_eq!(suggest("brand:samsumg")[0], "brand:samsung");
+    assert_eq!(suggest("samsumg banana")[0], "samsung banana");
+
+    // A sugestão usa a grafia do catálogo, não o termo normalizado.
+    assert_eq!(suggest("jens")[0], "jeans");
+
+    // Com resultados (ou sem correção possível), não há sugestões.
+    assert!(search::search(&system, &SearchRequest::new("samsung")).unwrap().suggestions.is_empty());
+    assert!(search::search(&system, &SearchRequest::new("banana")).unwrap().suggestions.is_empty());
+}
+
+#[test]
+fn test_faceted_filters_by_brand_category_and_tag() {
+    let system = graph_builder::build_system_from_data();
+
+    // "smart jbl slim": TV LG, TV Samsung, Soundbar JBL e Calça (slim).
+    let all = search::search(&system, &SearchRequest::new("smart jbl slim")).unwrap();
+    assert_eq!(all.hits.len(), 4);
+    assert_eq!(all.facets.categories.get("Eletrônicos"), Some(&3));
+    assert_eq!(all.facets.categories.get("Vestuário"), Some(&1));
+    assert_eq!(all.facets.brands.len(), 4);
+
+    // Filtro de categoria sem acento; a faceta de categoria continua mostrando as outras opções.
+    let electronics = search::search(&system, &SearchRequest::new("smart jbl slim").with_category("eletronicos")).unwrap();
+    let ids: BTreeSet<u32> = electronics.hits.iter().map(|hit| hit.product.id).collect();
+    assert_eq!(ids, BTreeSet::from([101, 102, 104]));
+    assert_eq!(electronics.facets.categories.get("Vestuário"), Some(&1));
+    assert_eq!(electronics.facets.brands.get("Levi's"), None, "A faceta de marca respeita o filtro de categoria.");
+
+    // Filtros combinados: marca + tag.
+    let samsung_tv = search::search(&system, &SearchRequest::new("smart").with_brand("SAMSUNG").with_tag("tv")).unwrap();
+    assert_eq!(samsung_tv.hits.len(), 1);
+    assert_eq!(samsung_tv.hits[0].product.id, 104);
+    assert_eq!(samsung_tv.facets.brands.get("LG"), Some(&1));
+
+    let nothing = search::search(&system, &SearchRequest::new("smart").with_tag("roupa")).unwrap();
+    assert!(nothing.hits.is_empty());
+}
+
+#[test]
+fn test_pagination_and_sort_orders() {
+    let system = graph_builder::build_system_from_data();
+    let query = "smart jbl slim vestido";
+    let ids = |request: &SearchRequest| -> Vec<u32> {
+        search::search(&system, request).unwrap().hits.iter().map(|hit| hit.product.id).collect()
+    };
+
+    // Páginas consecutivas não se sobrepõem e reconstroem a lista completa.
+    let full = ids(&SearchRequest::new(query));
+    assert_eq!(full.len(), 5);
+    let mut paged = Vec::new();
+    for offset in (0..6).step_by(2) {
+        let response = search::search(&system, &SearchRequest::new(query).with_page(offset, 2)).unwrap();
+        assert_eq!(response.total_hits, 5);
+        paged.extend(response.hits.iter().map(|hit| hit.product.id));
+    }
+    assert_eq!(paged, full);
+    assert!(ids(&SearchRequest::new(query).with_page(10, 2)).is_empty());
+
+    // Nome em ordem alfabética ignorando acentos e maiúsculas.
+    assert_eq!(ids(&SearchRequest::new(query).with_sort(SortOrder::Name)), vec![105, 101, 104, 102, 103]);
+    // Mais novos primeiro.
+    assert_eq!(ids(&SearchRequest::new(query).with_sort(SortOrder::Newest)), vec![103, 104, 105, 102, 101]);
+    // A Calça Jeans nunca foi comprada: fica por último em popularidade.
+    let popular = ids(&SearchRequest::new(query).with_sort(SortOrder::Popularity));
+    assert_eq!(popular.last(), Some(&105));
+    assert_eq!(popular, ids(&SearchRequest::new(query).with_sort(SortOrder::Popularity)), "A ordem deve ser determinística.");
+}
+
+#[test]
+fn test_synonym_expansion_from_file() {
+    let mut system = graph_builder::build_system_from_data();
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/synonyms.txt");
+    assert_eq!(system.load_synonyms(path).unwrap(), 6);
+
+    // Grupo: "televisor" alcança as TVs pelo Termo "tv", com pontuação abaixo da busca exata.
+    let score_of = |query: &str, id: u32| {
+        search::search_scored(&system, query).unwrap().into_iter().find(|(p, _)| p.id == id).map(|(_, score)| score)
+    };
+    for id in [101, 104] {
+        let exact = score_of("tv", id).unwrap();
+        let expanded = score_of("televisor", id).unwrap();
+        assert!((expanded - exact * search::SYNONYM_PENALTY).abs() < 1e-9);
+    }
+    // "televisão" deve trazer as TVs na frente, acima de qualquer correspondência parcial.
+    let ranked: Vec<u32> = search::search_scored(&system, "televisão").unwrap().iter().map(|(p, _)| p.id).collect();
+    assert_eq!(ranked[..2], [101, 104]);
+    let without = search::search(&system, &SearchRequest::new("televisor").with_synonyms(false)).unwrap();
+    assert!(without.hits.iter().all(|hit| hit.product.id != 101 && hit.product.id != 104));
+
+    // Alias de mão única: "som" traz a Soundbar, mas "soundbar" não expande para "som".
+    let ids: Vec<u32> = search::search_by_term(&system, "som").iter().map(|p| p.id).collect();
+    assert_eq!(ids, vec![102]);
+    assert_eq!(system.synonyms.expansions("som").collect::<Vec<_>>(), vec!["audio", "soundbar"]);
+    assert_eq!(system.synonyms.expansions("soundbar").count(), 0);
+
+    // Regras inválidas informam a linha.
+    let analyzer = PortugueseAnalyzer::default();
+    let err = SynonymMap::parse("tv, televisor\nsmart tv, televisor", &analyzer).unwrap_err();
+    assert!(matches!(&err, MegaStoreError::InvalidData(msg) if msg.starts_with("linha 2")));
+    assert!(SynonymMap::parse("tv", &analyzer).is_err());
+}
+
+#[test]
+fn test_phrase_and_proximity_queries() {
+    let mut system = graph_builder::build_system_from_data();
+    system.upsert_product(Product {
+        id: 106,
+        name: "TV Box Android Smart".to_string(),
+        brand: "Xiaomi".to_string(),
+        category: "Eletrônicos".to_string(),
+        tags: vec!["tv".to_string(), "smart".to_string()],
+        created_at: None,
+    });
+    let ids = |request: &SearchRequest| -> Vec<u32> {
+        search::search(&system, request).unwrap().hits.iter().map(|hit| hit.product.id).collect()
+    };
+
+    // Sem o bônus, o nome mais curto da TV Box vence; com ele, "Smart TV" (palavras vizinhas) sobe.
+    let position = |ids: &[u32], id: u32| ids.iter().position(|&other| other == id).unwrap();
+    let without = ids(&SearchRequest::new("smart tv").with_proximity_boost(0.0));
+    assert!(position(&without, 106) < position(&without, 101));
+    let with = ids(&SearchRequest::new("smart tv"));
+    assert!(position(&with, 101) < position(&with, 106));
+
+    // Frases exigem os termos exatos em posições consecutivas do mesmo campo.
+    assert_eq!(ids(&SearchRequest::new("\"smart tv\"")), vec![101, 104]);
+    assert!(ids(&SearchRequest::new("\"tv smart\"")).is_empty(), "Tags diferentes não formam frase.");
+    assert_eq!(ids(&SearchRequest::new("\"smart tv\" samsung").with_mode(MatchMode::And)), vec![104]);
+    assert_eq!(ids(&SearchRequest::new("\"calça jeans")), vec![105], "Aspas sem fechamento valem até o fim.");
+}
+
+#[test]
+fn test_boolean_query_language() {
+    let system = graph_builder::build_system_from_data();
+    let ids = |query: &str| -> BTreeSet<u32> {
+        search::try_search_by_term(&system, query).unwrap().iter().map(|p| p.id).collect()
+    };
+
+    assert_eq!(ids("brand:samsung AND (tv OR qled) -category:vestuário"), BTreeSet::from([104]));
+    assert_eq!(ids("(tv OR jeans) -brand:lg"), BTreeSet::from([104, 105]));
+    assert_eq!(ids("NOT categoria:eletronicos"), BTreeSet::from([103, 105]));
+    assert_eq!(ids("tag:tv"), BTreeSet::from([101, 104]));
+    assert_eq!(ids("name:\"smart tv\" AND samsung"), BTreeSet::from([104]));
+    assert_eq!(ids("marca:levis OR marca:zara"), BTreeSet::from([103, 105]));
+    // AND tem precedência sobre OR.
+    assert_eq!(ids("soundbar OR smart AND qled"), BTreeSet::from([102, 104]));
+
+    let parse_error = |query: &str| match search::try_search_by_term(&system, query) {
+        Err(MegaStoreError::QueryParse { position, message }) => (position, message),
+        other => panic!("esperado erro de sintaxe para {:?}, obtido {:?}", query, other),
+    };
+    assert_eq!(parse_error("brand:samsung AND").0, 14);
+    assert_eq!(parse_error("(tv OR qled").0, 0);
+    assert_eq!(parse_error("tv OR qled)").0, 10);
+    assert_eq!(parse_error("OR tv").0, 0);
+    assert_eq!(parse_error("tv -").0, 3);
+    assert_eq!(parse_error("tv -  ").1, "esperado um termo após '-'");
+    assert_eq!(parse_error("tv (de)").1, "grupo entre parênteses só tem stopwords");
+
+    // '-' entre espaços é separador, não negação.
+    assert_eq!(ids("tv - qled"), ids("tv qled"));
+    assert_eq!(ids("Smart TV - Samsung"), ids("Smart TV Samsung"));
+
+    // "nome:" desconhecido ou sem valor não é campo: as palavras são buscadas normalmente.
+    assert_eq!(ids("Smart TV 4K: LG"), ids("Smart TV 4K LG"));
+    assert_eq!(ids("12:00 tv"), BTreeSet::from([101, 104]));
+    assert_eq!(ids("cor:azul OR jeans"), BTreeSet::from([105]));
+    assert_eq!(ids("brand: samsung"), BTreeSet::from([104]));
+
+    // Negação dentro de OR soma o complemento; solta na consulta, exclui.
+    assert_eq!(ids("tv OR NOT qled"), BTreeSet::from([101, 102, 103, 104, 105]));
+    assert_eq!(ids("qled OR -tv"), BTreeSet::from([102, 103, 104, 105]));
+    assert_eq!(ids("smart tv -qled"), BTreeSet::from([101]));
+
+    let err = search::try_search_by_term(&system, "tv AND ()").unwrap_err();
+    assert_eq!(err.to_string(), "consulta inválida (posição 7): grupo '()' sem termos");
+}
+
+#[test]
+fn test_search_result_highlighting() {
+    let system = graph_builder::build_system_from_data();
+    let brackets = HighlightMarkers::new("[", "]");
+
+    // Os offsets apontam para a palavra original, com acento.
+    let response = search::search(&system, &SearchRequest::new("calças")).unwrap();
+    let hit = &response.hits[0];
+    assert_eq!(hit.product.id, 105);
+    assert_eq!(hit.highlights.name, vec![0..6]);
+    assert_eq!(hit.highlighted_name(&brackets), "[Calça] Jeans Slim Fit");
+    assert_eq!(hit.highlighted_tags(&brackets), vec!["[calca]"]);
+
+    let response = search::search(&system, &SearchRequest::new("smart tv").with_page(0, 1)).unwrap();
+    let hit = &response.hits[0];
+    assert_eq!(hit.highlighted_name(&HighlightMarkers::default()), format!("<mark>Smart</mark> <mark>TV</mark> {}", &hit.product.name[9..]));
+    assert_eq!(hit.highlights.tags.len(), 2);
+
+    // Termos negados não são destacados; campos só destacam o próprio campo.
+    let response = search::search(&system, &SearchRequest::new("tv -brand:lg")).unwrap();
+    assert_eq!(response.hits[0].highlighted_name(&brackets), "Smart [TV] QLED Samsung 65");
+    let response = search::search(&system, &SearchRequest::new("brand:samsung")).unwrap();
+    assert!(response.hits[0].highlights.is_empty());
+
+    // Trechos sobrepostos são unidos; trechos inválidos são ignorados.
+    assert_eq!(highlight::highlight("abcdef", &[3..5, 0..2, 1..4], &brackets), "[abcde]f");
+    assert_eq!(highlight::highlight("Calça", &[4..5, 9..12], &brackets), "Calça");
+}
+
+#[test]
+fn test_search_explanation() {
+    let system = graph_builder::build_system_from_data();
+
+    let plain = search::search(&system, &SearchRequest::new("smart tv")).unwrap();
+    assert!(plain.hits.iter().all(|hit| hit.explanation.is_none()));
+
+    let response = search::search(&system, &SearchRequest::new("smart tv").with_brand("lg").with_explain(true)).unwrap();
+    let hit = &response.hits[0];
+    let explanation = hit.explanation.as_ref().unwrap();
+    assert_eq!(explanation.product_id, 101);
+    assert_eq!(explanation.matched_terms, 2);
+    let terms: BTreeSet<&str> = explanation.terms.iter().map(|t| t.term.as_str()).collect();
+    assert_eq!(terms, BTreeSet::from(["smart", "tv"]));
+    // "Smart TV 4K LG 55" com as tags "tv" e "smart": uma aresta no nome e outra nas tags.
+    assert!(explanation.terms.iter().all(|t| t.edges.len() == 2));
+    let base: f64 = explanation.terms.iter().map(|t| t.contribution).sum();
+    assert!((base - explanation.base_score).abs() < 1e-9);
+    assert_eq!(explanation.boosts.len(), 1);
+    assert!((explanation.score - hit.score).abs() < 1e-9);
+    assert!((explanation.base_score * explanation.boosts[0].factor - explanation.score).abs() < 1e-9);
+
+    let json = serde_json::to_value(explanation).unwrap();
+    assert_eq!(json["terms"][0]["kind"], "exact");
+    assert_eq!(json["terms"][0]["edges"][0]["kind"], "CONTAINS_TERM");
+    assert_eq!(json["filters"]["brand"], "lg");
+
+    // Correspondência aproximada mostra o tipo e o fator aplicado.
+    let fuzzy = search::search(&system, &SearchRequest::new("samsumg").with_fuzzy(true).with_explain(true)).unwrap();
+    let term = &fuzzy.hits[0].explanation.as_ref().unwrap().terms[0];
+    assert_eq!((term.query_term.as_str(), term.term.as_str()), ("samsumg", "samsung"));
+    assert_eq!(term.kind, MatchKind::Fuzzy);
+    assert_eq!(term.penalty, search::FUZZY_DISTANCE_PENALTY);
+}
+
+#[test]
+fn test_weighted_interactions_in_recommendations() {
+    let json = r#"{
+        "products": [
+            {"id": 101, "name": "Smart TV", "brand": "LG", "category": "Eletrônicos", "tags": []},
+            {"id": 102, "name": "Soundbar", "brand": "JBL", "category": "Eletrônicos", "tags": []},
+            {"id": 103, "name": "Suporte de Parede", "brand": "ELG", "category": "Eletrônicos", "tags": []},
+            {"id": 105, "name": "Cabo HDMI", "brand": "Multilaser", "category": "Eletrônicos", "tags": []}
+        ],
+        "users": [{"id": 1, "name": "Ana"}, {"id": 2, "name": "Bia"}, {"id": 3, "name": "Caio"}, {"id": 4, "name": "Davi"}],
+        "interactions": [
+            {"user_id": 1, "product_id": 101, "type": "BOUGHT"},
+            {"user_id": 2, "product_id": 101, "type": "VIEWED"},
+            {"user_id": 2, "product_id": 102, "type": "BOUGHT"},
+            {"user_id": 3, "product_id": 101, "type": "BOUGHT"},
+            {"user_id": 3, "product_id": 103, "type": "ADDED_TO_CART"},
+            {"user_id": 4, "product_id": 101, "type": "RATED", "rating": 1},
+            {"user_id": 4, "product_id": 105, "type": "WISHLISTED"}
+        ]
+    }"#;
+    let mut system = graph_builder::build_system_from_reader(json.as_bytes()).unwrap();
+
+    // Caio também comprou a TV (vizinho mais próximo que Bia, que só a viu); Davi deu nota 1
+    // e, por não ter gostado da TV, não é vizinho: o cabo da sua lista de desejos não aparece.
+    let overlap = RecommendOptions::new().with_similarity(NeighborSimilarity::Overlap);
+    let scored = recommender::recommend_with_options(&system, 1, &overlap).unwrap();
+    let ids: Vec<u32> = scored.iter().map(|(p, _)| p.id).collect();
+    assert_eq!(ids, vec![103, 102]);
+    assert_eq!(scored[0].1, 5.0 * 5.0 * 3.0);
+    for similarity in [NeighborSimilarity::Jaccard, NeighborSimilarity::Cosine] {
+        let scored = recommender::recommend_with_options(&system, 1, &overlap.clone().with_similarity(similarity)).unwrap();
+        assert!(scored.iter().all(|(p, _)| p.id != 105));
+    }
+
+    // Pesos configuráveis: valorizar visualizações aproxima Bia.
+    let weights = InteractionWeights { viewed: 10.0, ..InteractionWeights::default() };
+    let options = overlap.with_weights(weights).with_limit(1);
+    let ids: Vec<u32> = recommender::recommend_with_options(&system, 1, &options).unwrap().iter().map(|(p, _)| p.id).collect();
+    assert_eq!(ids, vec![102]);
+
+    let invalid = Interaction { user_id: 2, product_id: 103, interaction_type: InteractionType::Rated, rating: Some(6), timestamp: None, quantity: None };
+    assert!(matches!(system.record_interaction(&invalid), Err(MegaStoreError::InvalidData(_))));
+}
+
+#[test]
+fn test_time_decayed_recommendations() {
+    const DAY: u64 = 24 * 60 * 60;
+    let now = 1_735_689_600;
+    let json = format!(r#"{{
+        "products": [
+            {{"id": 101, "name": "Smart TV", "brand": "LG", "category": "Eletrônicos", "tags": []}},
+            {{"id": 102, "name": "Soundbar", "brand": "JBL", "category": "Eletrônicos", "tags": []}},
+            {{"id": 103, "name": "Suporte de Parede", "brand": "ELG", "category": "Eletrônicos", "tags": []}}
+        ],
+        "users": [{{"id": 1, "name": "Ana"}}, {{"id": 2, "name": "Bia"}}, {{"id": 3, "name": "Caio"}}],
+        "interactions": [
+            {{"user_id": 1, "product_id": 101, "type": "BOUGHT", "timestamp": {now}}},
+            {{"user_id": 2, "product_id": 101, "type": "BOUGHT", "timestamp": {year_ago}, "quantity": 2}},
+            {{"user_id": 2, "product_id": 102, "type": "BOUGHT", "timestamp": {year_ago}}},
+            {{"user_id": 3, "product_id": 101, "type": "VIEWED", "timestamp": {week_ago}}},
+            {{"user_id": 3, "product_id": 103, "type": "VIEWED", "timestamp": {week_ago}}}
+        ]
+    }}"#, now = now, year_ago = now - 365 * DAY, week_ago = now - 7 * DAY);
+    let system = graph_builder::build_system_from_reader(json.as_bytes()).unwrap();
+    let ids = |options: &RecommendOptions| -> Vec<u32> {
+        recommender::recommend_with_options(&system, 1, options).unwrap().iter().map(|(p, _)| p.id).collect()
+    };
+
+    // Sem decaimento, as compras antigas de Bia dominam.
+    let overlap = RecommendOptions::new().with_similarity(NeighborSimilarity::Overlap);
+    assert_eq!(ids(&overlap), vec![102, 103]);
+    // Com meia-vida de 30 dias, as visualizações recentes de Caio passam à frente.
+    let decayed = overlap.clone().with_half_life(Duration::from_secs(30 * DAY)).with_now(now);
+    assert_eq!(ids(&decayed), vec![103, 102]);
+
+    // Uma interação com a idade da meia-vida vale metade; a quantidade multiplica o peso da compra.
+    let scored = recommender::recommend_with_options(&system, 1, &overlap.with_half_life(Duration::from_secs(7 * DAY)).with_now(now)).unwrap();
+    let caio = scored.iter().find(|(p, _)| p.id == 103).unwrap().1;
+    assert!((caio - 5.0 * 0.5 * 0.5).abs() < 1e-9);
+    let purchase = Interaction { user_id: 1, product_id: 102, interaction_type: InteractionType::Bought, rating: None, timestamp: None, quantity: Some(3) };
+    assert_eq!(InteractionWeights::default().edge_weight(&GraphEdge::interaction(&purchase)), 15.0);
+
+    // Quantidade zero no catálogo é dado inválido, não interação pendente.
+    let zero_quantity = json.replace(r#""quantity": 2"#, r#""quantity": 0"#);
+    assert!(matches!(
+        graph_builder::build_system_from_reader(zero_quantity.as_bytes()),
+        Err(MegaStoreError::InvalidData(_))
+    ));
+}
+
+#[test]
+fn test_neighbor_similarity_in_recommendations() {
+    let json = r#"{
+        "products": [
+            {"id": 101, "name": "Smart TV", "brand": "LG", "category": "Eletrônicos", "tags": []},
+            {"id": 102, "name": "Soundbar", "brand": "JBL", "category": "Eletrônicos", "tags": []},
+            {"id": 103, "name": "Suporte de Parede", "brand": "ELG", "category": "Eletrônicos", "tags": []},
+            {"id": 104, "name": "Controle Universal", "brand": "Philips", "category": "Eletrônicos", "tags": []},
+            {"id": 105, "name": "Cabo HDMI", "brand": "Multilaser", "category": "Eletrônicos", "tags": []}
+        ],
+        "users": [{"id": 1, "name": "Ana"}, {"id": 2, "name": "Bia"}, {"id": 3, "name": "Caio"}],
+        "interactions": [
+            {"user_id": 1, "product_id": 101, "type": "BOUGHT"},
+            {"user_id": 1, "product_id": 102, "type": "BOUGHT"},
+            {"user_id": 1, "product_id": 103, "type": "BOUGHT"},
+            {"user_id": 2, "product_id": 101, "type": "BOUGHT"},
+            {"user_id": 2, "product_id": 102, "type": "BOUGHT"},
+            {"user_id": 2, "product_id": 103, "type": "BOUGHT"},
+            {"user_id": 2, "product_id": 104, "type": "BOUGHT"},
+            {"user_id": 3, "product_id": 101, "type": "BOUGHT", "quantity": 10},
+            {"user_id": 3, "product_id": 105, "type": "BOUGHT"}
+        ]
+    }"#;
+    let system = graph_builder::build_system_from_reader(json.as_bytes()).unwrap();
+    let ids = |options: &RecommendOptions| -> Vec<u32> {
+        recommender::recommend_with_options(&system, 1, options).unwrap().iter().map(|(p, _)| p.id).collect()
+    };
+
+    // Caio compartilha um único produto, mas em grande quantidade: o produto escalar o favorece.
+    assert_eq!(ids(&RecommendOptions::new().with_similarity(NeighborSimilarity::Overlap)), vec![105, 104]);
+    // Bia compartilha os três produtos de Ana: mais semelhante por Jaccard (3/4) e por cosseno.
+    assert_eq!(ids(&RecommendOptions::new().with_similarity(NeighborSimilarity::Jaccard)), vec![104, 105]);
+    assert_eq!(ids(&RecommendOptions::new()), vec![104, 105]);
+
+    let scored = recommender::recommend_with_options(&system, 1, &RecommendOptions::new().with_similarity(NeighborSimilarity::Jaccard)).unwrap();
+    assert!((scored[0].1 - 0.75 * 5.0).abs() < 1e-9);
+    assert!((scored[1].1 - 0.25 * 5.0).abs() < 1e-9);
+
+    // Limite de vizinhos: apenas o mais semelhante contribui.
+    assert_eq!(ids(&RecommendOptions::new().with_max_neighbors(Some(1))), vec![104]);
+}
+
+#[test]
+fn test_similar_products() {
+    let system = graph_builder::build_system_from_data();
+    let ids = |similar: &[(Product, f64)]| -> Vec<u32> { similar.iter().map(|(p, _)| p.id).collect() };
+
+    // Bob comprou a TV Samsung e a Soundbar: mesma categoria e compra em comum. A TV LG tem
+    // mesma categoria, tag "tv" e termos "smart tv" em comum. Produtos de vestuário ficam de fora.
+    let similar = item_similarity::similar_products(&system, 104, 5).unwrap();
+    assert_eq!(ids(&similar), vec![102, 101]);
+    assert!((similar[0].1 - (1.0 + 3.0)).abs() < 1e-9);
+
+    // Sem o sinal de compras em comum, o conteúdo decide.
+    let content_only = ItemSimilarityWeights { co_purchase: 0.0, ..ItemSimilarityWeights::default() };
+    let similar = item_similarity::similar_products_with_weights(&system, 104, 1, &content_only).unwrap();
+    assert_eq!(ids(&similar), vec![101]);
+
+    // Alice apenas viu a Soundbar: visualizações não contam como compra em comum com a TV LG.
+    let similar = item_similarity::similar_products(&system, 101, 5).unwrap();
+    assert_eq!(ids(&similar), vec![104, 102]);
+    assert_eq!(similar[1].1, 1.0);
+
+    assert!(matches!(item_similarity::similar_products(&system, 999, 5), Err(MegaStoreError::UnknownProduct(999))));
+}
+
+#[test]
+fn test_related_by_tag_edges() {
+    let tag_relations = |system: &megastore_busca_otimizada::models::SearchSystem, product_id: u32| -> Vec<(u32, f64)> {
+        let index = system.product_id_to_index[&product_id];
+        let mut relations: Vec<(u32, f64)> = system
+            .graph
+            .edges(index)
+            .filter(|edge| edge.weight().kind == EdgeType::RELATED_BY_TAG)
+            .map(|edge| match &system.graph[edge.target()] {
+                GraphNode::Product(product) => (product.id, edge.weight().weight.unwrap()),
+                _ => unreachable!(),
+            })
+            .collect();
+        relations.sort_by_key(|(id, _)| *id);
+        relations
+    };
+
+    // As duas TVs compartilham a tag "tv", presente em 2 dos 5 produtos.
+    let mut system = graph_builder::build_system_from_data();
+    assert_eq!(tag_relations(&system, 101), vec![(104, (1.0_f64 + 5.0 / 2.0).ln())]);
+    assert!(tag_relations(&system, 102).is_empty());
+
+    // Atualizações incrementais criam e removem as arestas nos dois sentidos.
+    system.upsert_product(Product { id: 106, name: "Biquíni Listrado".to_string(), brand: "Farm".to_string(), category: "Praia".to_string(), tags: vec!["verao".to_string()], created_at: None });
+    assert_eq!(tag_relations(&system, 103), vec![(106, (1.0_f64 + 6.0 / 2.0).ln())]);
+    let similar = item_similarity::similar_products(&system, 106, 5).unwrap();
+    assert_eq!(similar[0].0.id, 103);
+
+    // Eva só viu o vestido: os clientes vizinhos indicam a calça, as tags indicam o biquíni.
+    system.upsert_user(User { id: 5, name: "Eva".to_string() });
+    system.record_interaction(&Interaction { user_id: 5, product_id: 103, interaction_type: InteractionType::Viewed, rating: None, timestamp: None, quantity: None }).unwrap();
+    let ids = |options: &RecommendOptions| -> Vec<u32> {
+        recommender::recommend_with_options(&system, 5, options).unwrap().iter().map(|(p, _)| p.id).collect()
+    };
+    assert_eq!(ids(&RecommendOptions::new()), vec![105, 106]);
+    assert_eq!(ids(&RecommendOptions::new().with_tag_relation_weight(0.0)), vec![105]);
+
+    system.remove_product(106).unwrap();
+    assert!(tag_relations(&system, 103).is_empty());
+
+    // Catálogo de 200 produtos: "promo" em metade deles (comum demais para relacionar) e
+    // "vintage" em 30 (cada um com 29 candidatos, limitados aos mais relacionados).
+    let products = (1..=200)
+        .map(|id| {
+            let mut tags = Vec::new();
+            if id <= 100 {
+                tags.push("promo".to_string());
+            }
+            if id <= 30 {
+                tags.push("vintage".to_string());
+            }
+            Product { id, name: format!("Produto {}", id), brand: "Marca".to_string(), category: "Geral".to_string(), tags, created_at: None }
+        })
+        .collect();
+    let system = graph_builder::build_system(Catalog { products, users: vec![], interactions: vec![] }).unwrap();
+    let tag_edges = |product_id: u32| tag_relations(&system, product_id).len();
+    assert_eq!(tag_edges(50), 0, "Uma tag presente em metade do catálogo não cria arestas.");
+    assert_eq!(tag_edges(1), graph_builder::RELATED_BY_TAG_MAX_NEIGHBORS);
+    let total = system.graph.edge_weights().filter(|edge| edge.kind == EdgeType::RELATED_BY_TAG).count();
+    assert_eq!(total, 30 * graph_builder::RELATED_BY_TAG_MAX_NEIGHBORS);
+
+    // Catálogo pequeno: "verao" em 3 de 5 produtos passa da fração máxima, mas ainda relaciona.
+    let products = (1..=5)
+        .map(|id| {
+            let tags = if id <= 3 { vec!["verao".to_string()] } else { vec![] };
+            Product { id, name: format!("Produto {}", id), brand: "Marca".to_string(), category: "Geral".to_string(), tags, created_at: None }
+        })
+        .collect();
+    let system = graph_builder::build_system(Catalog { products, users: vec![], interactions: vec![] }).unwrap();
+    let idf = (1.0_f64 + 5.0 / 3.0).ln();
+    assert_eq!(tag_relations(&system, 1), vec![(2, idf), (3, idf)]);
+}